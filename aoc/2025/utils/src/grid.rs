@@ -8,15 +8,79 @@ use std::{
 
 use crate::types::Both;
 
+/// Clone and Copy for views borrowing a grid, which can always be copied
+/// (the derives would only implement them when T: Clone)
+macro_rules! impl_copy_view {
+    ($view:ident) => {
+        impl<T> Clone for $view<'_, T> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+        impl<T> Copy for $view<'_, T> {}
+    };
+}
+
+mod anim;
+mod automaton;
+mod beams;
+mod bits;
+mod compress;
+mod hex;
+mod image;
+mod lines;
+mod ops;
+mod parse;
+// public rather than re-exported: its free functions (contains, locate...)
+// only make sense qualified, as polygon::contains
+pub mod polygon;
+mod push;
+mod rect;
+mod regions;
+mod render;
+mod search;
+mod space;
+mod sparse;
+mod subgrid;
+mod symmetry;
+mod tilt;
+mod walk;
+mod wrapping;
+pub use anim::{Animation, read_recording};
+pub use automaton::{Automaton, Cell, Edges};
+pub use beams::{Energised, Timelines};
+pub use bits::BitGrid;
+pub use compress::{CompressedCell, Compression};
+pub use hex::{Hex, HexDir};
+pub use image::{Frames, Image, ImageFormat, Rgb};
+pub use lines::{bresenham, lattice_points};
+pub use ops::Coord;
+pub use parse::{GridParseError, Markers, Parsed};
+pub use push::{Objects, Occupant, PushError};
+pub use rect::Rect;
+pub use regions::{Edge, Region, Regions};
+pub use render::{Colour, Layer, Layered, Style};
+pub use search::FloodFill;
+pub use space::{Aabb, Delta3, Point3, VecMat3};
+pub use sparse::SparseGrid;
+pub use subgrid::{SubGrid, SubGridMut};
+pub use symmetry::{Transform, Transformed};
+pub use tilt::Slide;
+pub use walk::{Walk, Walker};
+pub use wrapping::Wrapping;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Point<T> {
     pub x: T,
     pub y: T,
 }
 
+/// A rectangular grid of values, stored row-major in a single flat buffer
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VecMat<T> {
-    data: Vec<Vec<T>>,
+    data: Vec<T>,
+    rows: usize,
+    cols: usize,
 }
 
 impl<T> IndexMut<Point<usize>> for VecMat<T> {
@@ -49,14 +113,66 @@ impl<T> TryFrom<Vec<Vec<T>>> for VecMat<T> {
     }
 }
 
+/// A rectangular grid of cells which can be rendered with [`MatDisplayWith`],
+/// some cells may be missing (eg in a [`SparseGrid`])
+pub trait GridCells<T> {
+    /// The (rows, cols) of the grid, like [`VecMat::shape`]
+    fn shape(&self) -> (usize, usize);
+    fn cell(&self, p: Point<usize>) -> Option<&T>;
+}
+
+impl<T> GridCells<T> for VecMat<T> {
+    fn shape(&self) -> (usize, usize) {
+        self.shape()
+    }
+
+    fn cell(&self, p: Point<usize>) -> Option<&T> {
+        self.get(p)
+    }
+}
+
+/// A [`MatDisplayWith`] rendering each cell with its own Display impl
+pub type PlainDisplay<'a, T> = MatDisplayWith<'a, T, fn(usize, usize, &T) -> &T>;
+
 pub struct MatDisplayWith<'a, T, F> {
-    grid: &'a VecMat<T>,
+    grid: &'a dyn GridCells<T>,
     f: F,
+    blank: char,
 }
 
 impl<'a, T, F> MatDisplayWith<'a, T, F> {
-    pub fn new(grid: &'a VecMat<T>, f: F) -> Self {
-        Self { grid, f }
+    pub fn new(grid: &'a dyn GridCells<T>, f: F) -> Self {
+        Self {
+            grid,
+            f,
+            blank: '.',
+        }
+    }
+
+    /// Set the character used to render missing cells (default '.')
+    pub fn with_blank(self, blank: char) -> Self {
+        Self { blank, ..self }
+    }
+
+    /// Call `cell` for every cell in row-major order (or `blank` for missing ones),
+    /// ending each row with a newline
+    fn fmt_cells(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        mut cell: impl FnMut(&mut fmt::Formatter<'_>, Point<usize>, &T) -> fmt::Result,
+    ) -> fmt::Result {
+        let (rows, cols) = self.grid.shape();
+        for i in 0..rows {
+            for j in 0..cols {
+                let p = Point::new(j, i);
+                match self.grid.cell(p) {
+                    Some(c) => cell(f, p, c)?,
+                    None => write!(f, "{}", self.blank)?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
     }
 
     pub fn fmt_highlight(
@@ -67,17 +183,14 @@ impl<'a, T, F> MatDisplayWith<'a, T, F> {
     where
         T: fmt::Display,
     {
-        for (i, row) in self.grid.data.iter().enumerate() {
-            for (j, c) in row.iter().enumerate() {
-                if highlights.contains(&Point::new(j, i)) {
-                    write!(f, "\x1b[1;31m{c}\x1b[0m")?;
-                } else {
-                    write!(f, "{c}")?;
-                }
+        let escape = Style::new().fg(Colour::Red).bold().escape();
+        self.fmt_cells(f, |f, p, c| {
+            if highlights.contains(&p) {
+                write!(f, "{escape}{c}\x1b[0m")
+            } else {
+                write!(f, "{c}")
             }
-            writeln!(f)?;
-        }
-        Ok(())
+        })
     }
 }
 
@@ -87,13 +200,7 @@ where
     U: fmt::Display,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for (i, row) in self.grid.data.iter().enumerate() {
-            for (j, c) in row.iter().enumerate() {
-                write!(f, "{}", (self.f)(j, i, c))?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        self.fmt_cells(f, |f, p, c| write!(f, "{}", (self.f)(p.x, p.y, c)))
     }
 }
 
@@ -113,7 +220,7 @@ impl Display for VecMat<u8> {
 }
 
 impl<T> VecMat<T> {
-    pub fn formatter(&self) -> MatDisplayWith<'_, T, fn(usize, usize, &T) -> &T> {
+    pub fn formatter(&self) -> PlainDisplay<'_, T> {
         MatDisplayWith::new(self, |_, _, c| c)
     }
 
@@ -129,16 +236,17 @@ impl<T> VecMat<T> {
         T: Clone,
     {
         let (rows, cols) = shape;
-        let data = vec![vec![val.clone(); cols]; rows];
-        Self { data }
+        let data = vec![val.clone(); rows * cols];
+        Self { data, rows, cols }
     }
 
     pub fn filled_with(shape: (usize, usize), f: impl Fn(Point<usize>) -> T) -> Self {
         let (rows, cols) = shape;
         let data = (0..rows)
-            .map(|y| (0..cols).map(|x| f(Point::new(x, y))).collect())
+            .flat_map(|y| (0..cols).map(move |x| Point::new(x, y)))
+            .map(f)
             .collect();
-        Self { data }
+        Self { data, rows, cols }
     }
 
     pub fn highlighted<'a, 'b: 'a>(
@@ -170,8 +278,9 @@ impl<T> VecMat<T> {
 
     pub fn new(data: Vec<Vec<T>>) -> Result<Self, Vec<Vec<T>>> {
         let rows = data.len();
+        let mut cols = 0;
         if rows != 0 {
-            let cols = data[0].len();
+            cols = data[0].len();
             if cols == 0 {
                 // invalid empty matrix
                 return Err(data);
@@ -180,7 +289,25 @@ impl<T> VecMat<T> {
                 return Err(data);
             }
         }
-        Ok(Self { data })
+        let data = data.into_iter().flatten().collect();
+        Ok(Self { data, rows, cols })
+    }
+
+    /// Construct a matrix from row-major flat data with rows of `cols` elements;
+    /// fails (giving back the data) if it can't be split evenly into rows
+    pub fn from_flat(data: Vec<T>, cols: usize) -> Result<Self, Vec<T>> {
+        if data.is_empty() {
+            return Ok(Self {
+                data,
+                rows: 0,
+                cols: 0,
+            });
+        }
+        if cols == 0 || !data.len().is_multiple_of(cols) {
+            return Err(data);
+        }
+        let rows = data.len() / cols;
+        Ok(Self { data, rows, cols })
     }
 
     // construct a matrix from an iterator (rows) of iterable (columns)
@@ -192,7 +319,7 @@ impl<T> VecMat<T> {
     }
 
     pub fn for_each(&self, mut f: impl FnMut(Point<usize>, &T)) {
-        for (y, row) in self.data.iter().enumerate() {
+        for (y, row) in self.iter_rows().enumerate() {
             for (x, cell) in row.iter().enumerate() {
                 f(Point::new(x, y), cell);
             }
@@ -200,7 +327,7 @@ impl<T> VecMat<T> {
     }
 
     pub fn for_each_mut(&mut self, mut f: impl FnMut(Point<usize>, &mut T)) {
-        for (y, row) in self.data.iter_mut().enumerate() {
+        for (y, row) in self.data.chunks_exact_mut(self.cols.max(1)).enumerate() {
             for (x, cell) in row.iter_mut().enumerate() {
                 f(Point::new(x, y), cell);
             }
@@ -208,28 +335,33 @@ impl<T> VecMat<T> {
     }
 
     pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
-        self.data.iter().map(|row| row.as_slice())
+        (0..self.rows).map(move |y| self.row_unchecked(y))
     }
 
     pub fn cols(&self) -> usize {
-        if self.is_empty() {
-            0
-        } else {
-            self.data[0].len()
-        }
+        self.cols
     }
 
     pub fn rows(&self) -> usize {
-        self.data.len()
+        self.rows
     }
 
     pub fn get_row(&self, idx: usize) -> Option<&[T]> {
-        self.data.get(idx).map(Vec::as_slice)
+        (idx < self.rows).then(|| self.row_unchecked(idx))
     }
     pub fn get_row_mut(&mut self, idx: usize) -> Option<&mut [T]> {
         // it's fine to give a &mut [T] reference since
         // that means callers can't change its length
-        self.data.get_mut(idx).map(Vec::as_mut_slice)
+        if idx >= self.rows {
+            return None;
+        }
+        let start = idx * self.cols;
+        Some(&mut self.data[start..start + self.cols])
+    }
+
+    fn row_unchecked(&self, idx: usize) -> &[T] {
+        let start = idx * self.cols;
+        &self.data[start..start + self.cols]
     }
 
     pub fn shape(&self) -> (usize, usize) {
@@ -237,16 +369,15 @@ impl<T> VecMat<T> {
     }
 
     pub fn iter_pos(&self) -> impl Iterator<Item = (Point<usize>, &T)> {
-        (0..self.rows()).flat_map(move |y| {
-            (0..self.cols()).map(move |x| {
-                let p = Point::new(x, y);
-                (p, self.get(p).unwrap())
-            })
+        self.iter_rows().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, c)| (Point::new(x, y), c))
         })
     }
 
     pub fn is_empty(&self) -> bool {
-        self.data.is_empty()
+        self.rows == 0
     }
 
     pub fn set(&mut self, p: Point<usize>, val: T) -> T {
@@ -254,27 +385,43 @@ impl<T> VecMat<T> {
     }
 
     pub fn get(&self, p: Point<usize>) -> Option<&T> {
-        self.data.get(p.y).and_then(|row| row.get(p.x))
+        self.data.get(self.checked_idx_for(p)?)
     }
 
     pub fn get_mut(&mut self, p: Point<usize>) -> Option<&mut T> {
-        self.data.get_mut(p.y).and_then(|row| row.get_mut(p.x))
+        let idx = self.checked_idx_for(p)?;
+        self.data.get_mut(idx)
+    }
+
+    /// Index into the flat storage for the given point, if it's in bounds
+    fn checked_idx_for(&self, p: Point<usize>) -> Option<usize> {
+        // lazily, as the index may overflow for out of bounds points
+        p.in_bounds((self.cols, self.rows))
+            .then(|| p.y * self.cols + p.x)
+    }
+
+    /// Point corresponding to the given index into the flat storage
+    fn point_for(&self, idx: usize) -> Point<usize> {
+        Point::new(idx % self.cols, idx / self.cols)
     }
 
     pub fn find(&self, value: &T) -> Option<Point<usize>>
     where
         T: PartialEq,
     {
-        for (y, row) in self.data.iter().enumerate() {
-            if let Some(x) = row.iter().position(|v| v == value) {
-                return Some(Point::new(x, y));
-            }
-        }
-        None
+        self.data
+            .iter()
+            .position(|v| v == value)
+            .map(|i| self.point_for(i))
     }
 
     pub fn iter_all(&self) -> impl Iterator<Item = &T> {
-        self.data.iter().flat_map(|v| v.iter())
+        self.data.iter()
+    }
+
+    /// The whole grid as a row-major slice
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
     }
 
     /// Obtain a new matrix whose rows are the coulmns of the current matrix
@@ -283,13 +430,14 @@ impl<T> VecMat<T> {
     where
         T: Clone,
     {
-        let data = (0..self.cols())
-            .map(|c| (0..self.rows()).map(|r| self.data[r][c].clone()).collect())
+        let data = (0..self.cols)
+            .flat_map(|c| (0..self.rows).map(move |r| self.data[r * self.cols + c].clone()))
             .collect();
-        let mat = Self { data };
-        // we don't need to check dimensions since it's aleady a matrix
-        debug_assert!(mat.data.len() == 0 || mat.data.iter().all(|x| x.len() == mat.data[0].len()));
-        mat
+        Self {
+            data,
+            rows: self.cols,
+            cols: self.rows,
+        }
     }
 }
 
@@ -451,14 +599,15 @@ impl Point<usize> {
         Some(Self { x, y })
     }
 
-    /// Check if the point is in bound for a chart with given size.
-    pub fn in_bounds(&self, (width, height): (usize, usize)) -> bool {
-        self.x < width && self.y < height
+    /// Check if the point is in bound for a chart with given size,
+    /// or more generally in the given rectangle.
+    pub fn in_bounds(&self, bounds: impl Into<Rect<usize>>) -> bool {
+        bounds.into().contains(*self)
     }
 
     /// Some(self) if the point is in bounds, else None
-    pub fn as_in_bounds(&self, (width, height): (usize, usize)) -> Option<Self> {
-        self.in_bounds((width, height)).then_some(*self)
+    pub fn as_in_bounds(&self, bounds: impl Into<Rect<usize>>) -> Option<Self> {
+        self.in_bounds(bounds).then_some(*self)
     }
 
     /// The point's neighbours in the 4 cardinal directions
@@ -612,3 +761,48 @@ impl Display for Dir {
         write!(f, "{}", c)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> VecMat<u8> {
+        let rows = ["abc", "def"].map(|s| s.bytes().collect::<Vec<_>>());
+        VecMat::try_from(rows.to_vec()).expect("rectangular")
+    }
+
+    #[test]
+    fn flat_indexing() {
+        let mut m = sample();
+        assert_eq!(m.shape(), (2, 3));
+        assert_eq!(m[Point::new(2, 1)], b'f');
+        assert_eq!(m.get(Point::new(3, 0)), None);
+        assert_eq!(m.get(Point::new(0, 2)), None);
+        assert_eq!(&m[1], b"def");
+        m.get_row_mut(0).unwrap()[1] = b'x';
+        assert_eq!(m.find(&b'x'), Some(Point::new(1, 0)));
+        assert_eq!(m.to_string(), "axc\ndef\n");
+    }
+
+    #[test]
+    fn transpose_and_ragged() {
+        let t = sample().transpose();
+        assert_eq!(t.shape(), (3, 2));
+        assert_eq!(t.to_string(), "ad\nbe\ncf\n");
+        assert!(VecMat::new(vec![vec![1, 2], vec![3]]).is_err());
+        assert_eq!(
+            VecMat::from_flat(vec![1, 2, 3, 4, 5, 6], 3).unwrap(),
+            VecMat::new(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap()
+        );
+    }
+
+    #[test]
+    fn wrapped_neighbours() {
+        let m = sample();
+        let corner = Point::new(0, 0);
+        assert_eq!(m.get(corner.neighbour(Dir::N)), None);
+        assert_eq!(m.get(corner.neighbour(Dir::NW)), None);
+        assert_eq!(m.get(corner.wrapping_add_signed(Dir::W.to_delta())), None);
+        assert_eq!(m.get(corner.neighbour(Dir::SE)), Some(&b'e'));
+    }
+}
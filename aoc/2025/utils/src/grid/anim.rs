@@ -0,0 +1,328 @@
+//! Play a sequence of rendered grids as an animation in the terminal,
+//! eg to watch a simulation step by step instead of scrolling through
+//! printed grids.
+//!
+//! Frames are anything implementing Display (a [`VecMat<u8>`](super::VecMat),
+//! a [`MatDisplayWith`](super::MatDisplayWith), a [`Layered`](super::Layered)
+//! rendering...), a simulation can produce them with eg
+//! `iter::from_fn(|| { step(&mut grid); Some(grid.to_string()) })`.
+//!
+//! On a terminal the controls are:
+//! - space: pause/resume
+//! - l / h: step one frame forward / back
+//! - L / H: jump 10 frames forward / back
+//! - 0 / $: go to the first / last frame seen so far
+//! - + / -: double / halve the frame rate
+//! - q: quit
+
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Separates frames in recordings, on a line of its own
+const FRAME_SEPARATOR: &str = "\x0c";
+const HEADER: &str = "#grid-animation fps=";
+/// Bounds of the frame rate when changed with + and -
+const MIN_FPS: f64 = 0.125;
+const MAX_FPS: f64 = 1000.0;
+
+/// Plays frames in the terminal or records them, see the [module docs](self)
+#[derive(Debug, Clone)]
+pub struct Animation {
+    fps: f64,
+    start_paused: bool,
+    record: Option<PathBuf>,
+}
+
+/// What the player should do next, after handling the pressed keys
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Controls {
+    pos: usize,
+    paused: bool,
+    fps: f64,
+    quit: bool,
+}
+
+impl Controls {
+    /// Handle a key press, `seen` being the number of frames rendered so far
+    fn key(&mut self, key: u8, seen: usize) {
+        let last = seen.saturating_sub(1);
+        match key {
+            b' ' => self.paused = !self.paused,
+            b'l' => self.pos += 1,
+            b'h' => self.pos = self.pos.saturating_sub(1),
+            b'L' => self.pos += 10,
+            b'H' => self.pos = self.pos.saturating_sub(10),
+            b'0' => self.pos = 0,
+            b'$' => self.pos = last,
+            b'+' => self.fps = (self.fps * 2.0).min(MAX_FPS),
+            b'-' => self.fps = (self.fps / 2.0).max(MIN_FPS),
+            b'q' => self.quit = true,
+            _ => return,
+        }
+        // stepping around pauses the playback
+        if matches!(key, b'l' | b'h' | b'L' | b'H' | b'0' | b'$') {
+            self.paused = true;
+        }
+    }
+}
+
+/// Puts the terminal in non-canonical no-echo mode while alive
+/// (without blocking reads), restoring it on drop
+struct RawMode {
+    saved: String,
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let out = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()?;
+    if !out.status.success() {
+        return Err(io::Error::other("stty failed"));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "min", "0", "time", "0"])?;
+        Ok(Self { saved })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+impl Animation {
+    /// Panics unless `fps` is positive and finite
+    pub fn new(fps: f64) -> Self {
+        assert!(
+            fps.is_finite() && fps > 0.0,
+            "frame rate must be positive and finite, not {fps}"
+        );
+        Self {
+            fps,
+            start_paused: false,
+            record: None,
+        }
+    }
+
+    /// Start paused on the first frame
+    pub fn paused(self) -> Self {
+        Self {
+            start_paused: true,
+            ..self
+        }
+    }
+
+    /// When not on a terminal, record to this file rather than to stdout
+    pub fn record_to(self, path: impl Into<PathBuf>) -> Self {
+        Self {
+            record: Some(path.into()),
+            ..self
+        }
+    }
+
+    /// Play the frames if stdin and stdout are a terminal, otherwise record them
+    pub fn play<D: Display>(&self, frames: impl IntoIterator<Item = D>) -> io::Result<()> {
+        if io::stdin().is_terminal() && io::stdout().is_terminal() {
+            return self.play_interactive(frames.into_iter().map(|f| f.to_string()));
+        }
+        match &self.record {
+            Some(path) => self.record(BufWriter::new(File::create(path)?), frames),
+            None => self.record(io::stdout().lock(), frames),
+        }
+        .map(|_| ())
+    }
+
+    /// Write the frames in a format [`Animation::replay`] can read,
+    /// returning the number of frames
+    pub fn record<D: Display>(
+        &self,
+        mut w: impl Write,
+        frames: impl IntoIterator<Item = D>,
+    ) -> io::Result<usize> {
+        writeln!(w, "{HEADER}{}", self.fps)?;
+        let mut count = 0;
+        for frame in frames {
+            let frame = frame.to_string();
+            writeln!(w, "{FRAME_SEPARATOR}")?;
+            write!(w, "{frame}")?;
+            if !frame.ends_with('\n') {
+                writeln!(w)?;
+            }
+            count += 1;
+        }
+        w.flush()?;
+        Ok(count)
+    }
+
+    /// Play a recording (at its own frame rate)
+    pub fn replay(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let (fps, frames) = read_recording(BufReader::new(File::open(path)?))?;
+        Self {
+            fps,
+            ..self.clone()
+        }
+        .play(frames)
+    }
+
+    fn play_interactive(&self, mut frames: impl Iterator<Item = String>) -> io::Result<()> {
+        let _raw = RawMode::enable()?;
+        let mut out = io::stdout().lock();
+        // alternate screen, hidden cursor
+        write!(out, "\x1b[?1049h\x1b[?25l")?;
+        let result = (|| {
+            let mut seen: Vec<String> = Vec::new();
+            let mut exhausted = false;
+            let mut ctl = Controls {
+                pos: 0,
+                paused: self.start_paused,
+                fps: self.fps,
+                quit: false,
+            };
+            let mut keys = [0; 16];
+            while !ctl.quit {
+                while seen.len() <= ctl.pos && !exhausted {
+                    match frames.next() {
+                        Some(frame) => seen.push(frame),
+                        None => exhausted = true,
+                    }
+                }
+                if seen.len() <= ctl.pos {
+                    // stay on the last frame once there's no more
+                    ctl.pos = seen.len().saturating_sub(1);
+                    ctl.paused = true;
+                }
+                let frame = seen.get(ctl.pos).map_or("", |f| f.as_str());
+                write!(out, "\x1b[H")?;
+                for line in frame.lines() {
+                    write!(out, "{line}\x1b[K\r\n")?;
+                }
+                let total = if exhausted {
+                    seen.len().to_string()
+                } else {
+                    "?".to_string()
+                };
+                let state = if ctl.paused { "paused" } else { "playing" };
+                write!(
+                    out,
+                    "frame {}/{total} {state} at {} fps\x1b[K\x1b[J",
+                    ctl.pos, ctl.fps
+                )?;
+                out.flush()?;
+                let deadline = Instant::now() + Duration::from_secs_f64(1.0 / ctl.fps);
+                let before = ctl;
+                while ctl == before && (ctl.paused || Instant::now() < deadline) {
+                    let n = io::stdin().lock().read(&mut keys)?;
+                    for &k in &keys[..n] {
+                        ctl.key(k, seen.len());
+                    }
+                    thread::sleep(Duration::from_millis(10));
+                }
+                if ctl == before {
+                    ctl.pos += 1;
+                }
+            }
+            Ok(())
+        })();
+        write!(out, "\x1b[?25h\x1b[?1049l")?;
+        out.flush()?;
+        result
+    }
+}
+
+/// Read a recording made by [`Animation::record`], as its frame rate and frames
+pub fn read_recording(r: impl BufRead) -> io::Result<(f64, Vec<String>)> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "not a grid animation");
+    let mut lines = r.lines();
+    let header = lines.next().ok_or_else(invalid)??;
+    let fps = header
+        .strip_prefix(HEADER)
+        .and_then(|fps| fps.parse().ok())
+        .filter(|fps: &f64| fps.is_finite() && *fps > 0.0)
+        .ok_or_else(invalid)?;
+    let mut frames: Vec<String> = Vec::new();
+    for line in lines {
+        let line = line?;
+        if line == FRAME_SEPARATOR {
+            frames.push(String::new());
+            continue;
+        }
+        let frame = frames.last_mut().ok_or_else(invalid)?;
+        frame.push_str(&line);
+        frame.push('\n');
+    }
+    Ok((fps, frames))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Point, VecMat};
+
+    #[test]
+    fn recording() {
+        let mut grid = VecMat::parse_bytes(["...", "..."]).unwrap();
+        let frames = (0..3).map(|i| {
+            grid[Point::new(i, i % 2)] = b'#';
+            grid.to_string()
+        });
+        let mut out = Vec::new();
+        let anim = Animation::new(12.5);
+        assert_eq!(anim.record(&mut out, frames).unwrap(), 3);
+        let (fps, frames) = read_recording(&out[..]).unwrap();
+        assert_eq!(fps, 12.5);
+        assert_eq!(frames, ["#..\n...\n", "#..\n.#.\n", "#.#\n.#.\n"]);
+        assert!(read_recording(&b"nope\n"[..]).is_err());
+        assert!(read_recording(&b"#grid-animation fps=0\n"[..]).is_err());
+    }
+
+    #[test]
+    fn controls() {
+        let mut ctl = Controls {
+            pos: 5,
+            paused: false,
+            fps: 10.0,
+            quit: false,
+        };
+        ctl.key(b'+', 20);
+        assert_eq!((ctl.fps, ctl.paused), (20.0, false));
+        ctl.key(b'H', 20);
+        assert_eq!((ctl.pos, ctl.paused), (0, true));
+        ctl.key(b'$', 20);
+        ctl.key(b'l', 20);
+        assert_eq!(ctl.pos, 20);
+        ctl.key(b' ', 20);
+        assert!(!ctl.paused);
+        for _ in 0..20 {
+            ctl.key(b'-', 20);
+        }
+        assert_eq!(ctl.fps, MIN_FPS);
+        for _ in 0..20 {
+            ctl.key(b'+', 20);
+        }
+        assert_eq!(ctl.fps, MAX_FPS);
+        ctl.key(b'x', 20);
+        ctl.key(b'q', 20);
+        assert!(ctl.quit);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_fps() {
+        Animation::new(0.0);
+    }
+}
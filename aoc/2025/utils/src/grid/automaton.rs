@@ -0,0 +1,346 @@
+use super::{Dir, Point, VecMat};
+
+/// What cells beyond the edges of an [`Automaton`]'s grid look like
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edges<T> {
+    /// There's nothing there: cells on the edges have fewer neighbours
+    Wall,
+    /// The grid wraps around like a torus
+    Wrap,
+    /// The grid is infinite and filled with the given background value,
+    /// growing as needed to hold the other values
+    Expand(T),
+}
+
+/// A cell as seen by an automaton's rule, with access to its neighbourhood
+#[derive(Debug)]
+pub struct Cell<'a, T> {
+    /// The cell's position, relative to the initial grid's top left corner
+    /// (cells can be above or left of it with [`Edges::Expand`])
+    pub pos: Point<isize>,
+    pub value: &'a T,
+    local: Point<usize>,
+    grid: &'a VecMat<T>,
+    edges: &'a Edges<T>,
+    dirs: &'a [Dir],
+}
+
+impl<'a, T> Cell<'a, T> {
+    /// The neighbour in direction `dir` (even if it's not part of the
+    /// automaton's neighbourhood), None if beyond a wall
+    pub fn neighbour(&self, dir: Dir) -> Option<&'a T> {
+        let n = self.local.neighbour(dir);
+        if let Some(v) = self.grid.get(n) {
+            return Some(v);
+        }
+        match self.edges {
+            Edges::Wall => None,
+            Edges::Wrap => {
+                let d = dir.to_delta::<isize>();
+                let wrap = |v: usize, d: isize, len: usize| {
+                    (v as isize + d).rem_euclid(len as isize) as usize
+                };
+                let x = wrap(self.local.x, d.dx, self.grid.cols());
+                let y = wrap(self.local.y, d.dy, self.grid.rows());
+                self.grid.get(Point::new(x, y))
+            }
+            Edges::Expand(background) => Some(background),
+        }
+    }
+
+    /// The neighbours in the automaton's neighbourhood
+    pub fn neighbours(&self) -> impl Iterator<Item = (Dir, &'a T)> {
+        self.dirs
+            .iter()
+            .filter_map(|&dir| Some((dir, self.neighbour(dir)?)))
+    }
+
+    /// Number of neighbours in the neighbourhood for which `pred` holds
+    pub fn count(&self, pred: impl Fn(&T) -> bool) -> usize {
+        self.neighbours().filter(|(_, v)| pred(v)).count()
+    }
+}
+
+/// A grid where every cell is updated at once at each generation
+/// according to a rule depending on the cell and its neighbours,
+/// until a fixpoint or a number of generations
+#[derive(Debug, Clone)]
+pub struct Automaton<T> {
+    grid: VecMat<T>,
+    /// Where the next generation is computed
+    back: VecMat<T>,
+    dirs: &'static [Dir],
+    edges: Edges<T>,
+    generation: usize,
+    origin: Point<isize>,
+}
+
+impl<T: Clone + PartialEq> Automaton<T> {
+    /// An automaton starting from `grid`, where the neighbourhood of a cell
+    /// is in the given directions (eg [`Dir::CROSS`] or [`Dir::ALL`])
+    pub fn new(grid: VecMat<T>, dirs: &'static [Dir], edges: Edges<T>) -> Self {
+        Self {
+            back: grid.clone(),
+            grid,
+            dirs,
+            edges,
+            generation: 0,
+            origin: Point::new(0, 0),
+        }
+    }
+
+    pub fn grid(&self) -> &VecMat<T> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> VecMat<T> {
+        self.grid
+    }
+
+    /// Number of steps taken so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// The position of the grid's top left corner relative to the initial one's,
+    /// only moves with [`Edges::Expand`]
+    pub fn origin(&self) -> Point<isize> {
+        self.origin
+    }
+
+    /// The value at `pos` (relative to the initial grid like [`Cell::pos`]),
+    /// None if outside the grid (and not in the background)
+    pub fn get(&self, pos: Point<isize>) -> Option<&T> {
+        let local = Point::new(pos.x - self.origin.x, pos.y - self.origin.y);
+        let inside = usize::try_from(local.x)
+            .ok()
+            .zip(usize::try_from(local.y).ok())
+            .and_then(|(x, y)| self.grid.get(Point::new(x, y)));
+        match (&self.edges, inside) {
+            (Edges::Expand(background), None) => Some(background),
+            (_, v) => v,
+        }
+    }
+
+    /// Compute the next generation of row y into `row`
+    fn next_row<F>(&self, y: usize, row: &mut [T], rule: &F)
+    where
+        F: Fn(&Cell<'_, T>) -> T,
+    {
+        for (x, out) in row.iter_mut().enumerate() {
+            let local = Point::new(x, y);
+            let cell = Cell {
+                pos: Point::new(self.origin.x + x as isize, self.origin.y + y as isize),
+                value: &self.grid[local],
+                local,
+                grid: &self.grid,
+                edges: &self.edges,
+                dirs: self.dirs,
+            };
+            *out = rule(&cell);
+        }
+    }
+
+    /// Advance one generation, filling the back buffer with `fill`;
+    /// returns whether anything changed
+    fn advance(&mut self, fill: impl FnOnce(&Self, &mut VecMat<T>)) -> bool {
+        if let Edges::Expand(background) = &self.edges {
+            // make room for the cells next to the edges to come alive
+            let (rows, cols) = self.grid.shape();
+            let old = &self.grid;
+            self.grid = VecMat::filled_with((rows + 2, cols + 2), |p| {
+                let inner = Point::new(p.x.wrapping_sub(1), p.y.wrapping_sub(1));
+                old.get(inner).unwrap_or(background).clone()
+            });
+            self.origin = Point::new(self.origin.x - 1, self.origin.y - 1);
+        }
+        if self.back.shape() != self.grid.shape() {
+            self.back = self.grid.clone();
+        }
+        // take the buffer out so `fill` can borrow the rest
+        let empty = VecMat {
+            data: Vec::new(),
+            rows: 0,
+            cols: 0,
+        };
+        let mut back = std::mem::replace(&mut self.back, empty);
+        fill(self, &mut back);
+        let changed = back != self.grid;
+        self.back = std::mem::replace(&mut self.grid, back);
+        self.generation += 1;
+        if matches!(self.edges, Edges::Expand(_)) {
+            self.trim();
+        }
+        changed
+    }
+
+    /// Remove the edge rows and columns holding only background
+    fn trim(&mut self) {
+        let Edges::Expand(background) = &self.edges else {
+            return;
+        };
+        let grid = &self.grid;
+        let empty_row = |y: usize| grid.row(y).all(|(_, v)| v == background);
+        let empty_col = |x: usize| grid.column(x).all(|(_, v)| v == background);
+        let (rows, cols) = grid.shape();
+        // always keep at least one cell
+        let top = (0..rows - 1).take_while(|&y| empty_row(y)).count();
+        let bottom = (top + 1..rows).rev().take_while(|&y| empty_row(y)).count();
+        let left = (0..cols - 1).take_while(|&x| empty_col(x)).count();
+        let right = (left + 1..cols).rev().take_while(|&x| empty_col(x)).count();
+        if (top, bottom, left, right) == (0, 0, 0, 0) {
+            return;
+        }
+        let shape = (rows - top - bottom, cols - left - right);
+        self.grid = grid
+            .sub(Point::new(left, top), shape)
+            .expect("trimmed region is inside the grid")
+            .to_vecmat();
+        self.origin = Point::new(self.origin.x + left as isize, self.origin.y + top as isize);
+    }
+
+    /// Advance one generation, every cell becoming `rule(cell)`;
+    /// returns whether anything changed (false means we reached a fixpoint)
+    pub fn step<F>(&mut self, rule: F) -> bool
+    where
+        F: Fn(&Cell<'_, T>) -> T,
+    {
+        self.advance(|this, back| {
+            let cols = back.cols();
+            for (y, row) in back.data.chunks_exact_mut(cols.max(1)).enumerate() {
+                this.next_row(y, row, &rule);
+            }
+        })
+    }
+
+    /// Step at most `generations` times, stopping early at a fixpoint;
+    /// returns whether a fixpoint was reached
+    pub fn run<F>(&mut self, generations: usize, rule: F) -> bool
+    where
+        F: Fn(&Cell<'_, T>) -> T,
+    {
+        (0..generations).any(|_| !self.step(&rule))
+    }
+
+    /// Step until nothing changes, returning the number of generations
+    /// (which never ends if there's no fixpoint, eg with oscillators)
+    pub fn run_to_fixpoint<F>(&mut self, rule: F) -> usize
+    where
+        F: Fn(&Cell<'_, T>) -> T,
+    {
+        while self.step(&rule) {}
+        self.generation
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Clone + PartialEq + Send + Sync> Automaton<T> {
+    /// Like [`Automaton::step`], computing the rows in parallel
+    pub fn par_step<F>(&mut self, rule: F) -> bool
+    where
+        F: Fn(&Cell<'_, T>) -> T + Sync,
+    {
+        use rayon::prelude::*;
+        self.advance(|this, back| {
+            let cols = back.cols();
+            back.data
+                .par_chunks_exact_mut(cols.max(1))
+                .enumerate()
+                .for_each(|(y, row)| this.next_row(y, row, &rule));
+        })
+    }
+
+    /// Like [`Automaton::run`], computing the rows in parallel
+    pub fn par_run<F>(&mut self, generations: usize, rule: F) -> bool
+    where
+        F: Fn(&Cell<'_, T>) -> T + Sync,
+    {
+        (0..generations).any(|_| !self.par_step(&rule))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(cell: &Cell<'_, bool>) -> bool {
+        matches!((cell.value, cell.count(|&v| v)), (true, 2) | (_, 3))
+    }
+
+    fn cells(rows: &[&str]) -> VecMat<bool> {
+        let grid = VecMat::parse_bytes(rows.iter().copied()).unwrap();
+        VecMat::filled_with(grid.shape(), |p| grid[p] == b'#')
+    }
+
+    #[test]
+    fn garden() {
+        // the garden from 2023 day 21's example, cells reached at each step
+        let garden = VecMat::parse_bytes([
+            "...........",
+            ".....###.#.",
+            ".###.##..#.",
+            "..#.#...#..",
+            "....#.#....",
+            ".##..S####.",
+            ".##..#...#.",
+            ".......##..",
+            ".##.#.####.",
+            ".##..##.##.",
+            "...........",
+        ])
+        .unwrap();
+        let mut auto = Automaton::new(garden, &Dir::CROSS, Edges::Wall);
+        let reached = auto.run(6, |cell| match cell.value {
+            b'#' => b'#',
+            _ if cell.count(|&v| v == b'S') > 0 => b'S',
+            _ => b'.',
+        });
+        assert!(!reached);
+        assert_eq!(auto.generation(), 6);
+        assert_eq!(auto.grid().iter_all().filter(|&&c| c == b'S').count(), 16);
+    }
+
+    #[test]
+    fn life_edges() {
+        let blinker = cells(&[".....", ".....", ".###.", ".....", "....."]);
+        let mut auto = Automaton::new(blinker.clone(), &Dir::ALL, Edges::Wall);
+        auto.step(life);
+        assert_eq!(auto.grid(), &blinker.transpose());
+        assert!(!auto.run(10, life));
+        // on a 3x3 torus every cell sees all the others
+        let mut auto = Automaton::new(cells(&["...", "###", "..."]), &Dir::ALL, Edges::Wrap);
+        // all alive, then all dead, then a generation where nothing changes
+        assert_eq!(auto.run_to_fixpoint(life), 3);
+        assert!(auto.grid().iter_all().all(|&v| !v));
+        // a glider moves by (1, 1) every 4 generations
+        let glider = cells(&[".#.", "..#", "###"]);
+        let mut auto = Automaton::new(glider.clone(), &Dir::ALL, Edges::Expand(false));
+        auto.run(8, life);
+        assert_eq!(auto.grid(), &glider);
+        assert_eq!(auto.origin(), Point::new(2, 2));
+        assert_eq!(auto.get(Point::new(4, 4)), Some(&true));
+        assert_eq!(auto.get(Point::new(-100, 4)), Some(&false));
+    }
+
+    #[test]
+    fn empty() {
+        let empty = VecMat::new(vec![]).unwrap();
+        let mut auto = Automaton::new(empty.clone(), &Dir::ALL, Edges::Wall);
+        assert!(!auto.step(life));
+        assert_eq!(auto.grid(), &empty);
+        let mut auto = Automaton::new(empty, &Dir::ALL, Edges::Expand(false));
+        assert_eq!(auto.run_to_fixpoint(life), 1);
+        assert_eq!(auto.get(Point::new(0, 0)), Some(&false));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel() {
+        let glider = cells(&[".#.", "..#", "###"]);
+        let mut seq = Automaton::new(glider.clone(), &Dir::ALL, Edges::Expand(false));
+        let mut par = seq.clone();
+        seq.run(7, life);
+        par.par_run(7, life);
+        assert_eq!((seq.grid(), seq.origin()), (par.grid(), par.origin()));
+    }
+}
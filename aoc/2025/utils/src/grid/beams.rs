@@ -0,0 +1,271 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::{Dir, Point, VecMat};
+
+impl Dir {
+    /// The direction a beam heading `self` leaves a `/` mirror in
+    pub fn reflect_slash(self) -> Self {
+        let (dx, dy) = self.to_i8_offset();
+        Self::from_i8_offset((-dy, -dx))
+    }
+
+    /// The direction a beam heading `self` leaves a `\` mirror in
+    pub fn reflect_backslash(self) -> Self {
+        let (dx, dy) = self.to_i8_offset();
+        Self::from_i8_offset((dy, dx))
+    }
+
+    /// The two directions perpendicular to this one (counterclockwise first),
+    /// eg where a beam goes when hitting a splitter side-on
+    pub fn perpendicular(self) -> [Self; 2] {
+        [self.counterclockwise_cross(), self.clockwise_cross()]
+    }
+
+    fn from_i8_offset(offset: (i8, i8)) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|d| d.to_i8_offset() == offset)
+            .unwrap()
+    }
+}
+
+/// The cells beams went through, see [`VecMat::beams`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Energised {
+    /// For each cell, a bitmask of the directions beams entered it heading
+    /// (by [`Dir::id`])
+    pub headings: VecMat<u8>,
+}
+
+impl Energised {
+    /// Number of cells reached by a beam
+    pub fn count(&self) -> usize {
+        self.headings.iter_all().filter(|&&h| h != 0).count()
+    }
+
+    pub fn contains(&self, p: Point<usize>) -> bool {
+        self.headings.get(p).is_some_and(|&h| h != 0)
+    }
+
+    /// The directions beams entered `p` heading
+    pub fn headings_at(&self, p: Point<usize>) -> impl Iterator<Item = Dir> + use<> {
+        let mask = self.headings.get(p).copied().unwrap_or(0);
+        Dir::ALL
+            .into_iter()
+            .filter(move |d| mask & (1 << d.id()) != 0)
+    }
+}
+
+/// How many distinct beam histories there are, see [`VecMat::beam_timelines`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timelines {
+    /// For each cell, the number of timelines going through it
+    pub reaching: VecMat<usize>,
+    /// The number of timelines in total, ie ending by leaving the grid
+    /// or in a cell sending the beam nowhere
+    pub total: usize,
+}
+
+type State = (Point<usize>, Dir);
+
+impl<T> VecMat<T> {
+    /// The cells in the grid a beam would go to when leaving `p` heading `dir`
+    fn beam_targets<R>(
+        &self,
+        (p, dir): State,
+        f: &mut impl FnMut(Point<usize>, &T, Dir) -> R,
+    ) -> Vec<Option<State>>
+    where
+        R: IntoIterator<Item = Dir>,
+    {
+        f(p, &self[p], dir)
+            .into_iter()
+            .map(|out| {
+                let n = p.neighbour(out);
+                self.get(n).map(|_| (n, out))
+            })
+            .collect()
+    }
+
+    /// Propagate a beam entering `start` heading `dir`; when a beam enters a
+    /// cell heading `dir`, it continues in each of `f(cell, value, dir)`
+    /// (none if it's absorbed, several if it's split)
+    pub fn beams<R>(
+        &self,
+        start: Point<usize>,
+        dir: Dir,
+        mut f: impl FnMut(Point<usize>, &T, Dir) -> R,
+    ) -> Energised
+    where
+        R: IntoIterator<Item = Dir>,
+    {
+        let mut headings = VecMat::filled(self.shape(), &0u8);
+        let mut stack = vec![(start, dir)];
+        while let Some((p, dir)) = stack.pop() {
+            let Some(mask) = headings.get_mut(p) else {
+                continue;
+            };
+            let bit = 1 << dir.id();
+            if *mask & bit != 0 {
+                continue;
+            }
+            *mask |= bit;
+            stack.extend(self.beam_targets((p, dir), &mut f).into_iter().flatten());
+        }
+        Energised { headings }
+    }
+
+    /// Like [`VecMat::beams`], but count the distinct timelines, where each
+    /// split makes one timeline per outgoing beam; None if beams can loop
+    /// (there'd be infinitely many timelines)
+    pub fn beam_timelines<R>(
+        &self,
+        start: Point<usize>,
+        dir: Dir,
+        mut f: impl FnMut(Point<usize>, &T, Dir) -> R,
+    ) -> Option<Timelines>
+    where
+        R: IntoIterator<Item = Dir>,
+    {
+        // discover the reachable states and how many ways in each one has
+        let mut targets: HashMap<State, Vec<Option<State>>> = HashMap::new();
+        let mut ways_in: HashMap<State, usize> = HashMap::new();
+        let mut stack = Vec::new();
+        if self.get(start).is_some() {
+            stack.push((start, dir));
+        }
+        while let Some(state) = stack.pop() {
+            if targets.contains_key(&state) {
+                continue;
+            }
+            let next = self.beam_targets(state, &mut f);
+            for &n in next.iter().flatten() {
+                *ways_in.entry(n).or_default() += 1;
+                stack.push(n);
+            }
+            targets.insert(state, next);
+        }
+        // then count the timelines in topological order
+        let mut reaching = VecMat::filled(self.shape(), &0);
+        let mut counts = HashMap::from([((start, dir), 1)]);
+        let mut total = 0;
+        let mut queue: VecDeque<_> = targets
+            .keys()
+            .filter(|s| !ways_in.contains_key(s))
+            .copied()
+            .collect();
+        let mut done = 0;
+        while let Some(state) = queue.pop_front() {
+            done += 1;
+            let count = counts[&state];
+            reaching[state.0] += count;
+            let next = &targets[&state];
+            if next.is_empty() {
+                total += count;
+            }
+            for n in next {
+                let Some(n) = *n else {
+                    total += count;
+                    continue;
+                };
+                *counts.entry(n).or_default() += count;
+                let ways = ways_in.get_mut(&n).unwrap();
+                *ways -= 1;
+                if *ways == 0 {
+                    queue.push_back(n);
+                }
+            }
+        }
+        (done == targets.len()).then_some(Timelines { reaching, total })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contraption(_: Point<usize>, &c: &u8, dir: Dir) -> Vec<Dir> {
+        match c {
+            b'/' => vec![dir.reflect_slash()],
+            b'\\' => vec![dir.reflect_backslash()],
+            b'|' if dir.is_horizontal() => dir.perpendicular().to_vec(),
+            b'-' if dir.is_vertical() => dir.perpendicular().to_vec(),
+            _ => vec![dir],
+        }
+    }
+
+    #[test]
+    fn mirrors() {
+        assert_eq!(Dir::E.reflect_slash(), Dir::N);
+        assert_eq!(Dir::S.reflect_slash(), Dir::W);
+        assert_eq!(Dir::E.reflect_backslash(), Dir::S);
+        assert_eq!(Dir::NE.reflect_slash(), Dir::NE);
+        assert_eq!(Dir::NE.reflect_backslash(), Dir::SW);
+        assert_eq!(Dir::N.perpendicular(), [Dir::W, Dir::E]);
+        // the contraption from 2023 day 16's example
+        let m = VecMat::parse_bytes([
+            r".|...\....",
+            r"|.-.\.....",
+            r".....|-...",
+            r"........|.",
+            r"..........",
+            r".........\",
+            r"..../.\\..",
+            r".-.-/..|..",
+            r".|....-|.\",
+            r"..//.|....",
+        ])
+        .unwrap();
+        let e = m.beams(Point::new(0, 0), Dir::E, contraption);
+        assert_eq!(e.count(), 46);
+        assert!(e.headings_at(Point::new(1, 0)).any(|d| d == Dir::E));
+        assert_eq!(m.beams(Point::new(3, 0), Dir::S, contraption).count(), 51);
+        assert_eq!(
+            m.beam_timelines(Point::new(0, 0), Dir::E, contraption),
+            None
+        );
+    }
+
+    #[test]
+    fn tachyons() {
+        // the manifold from 2025 day 7's example
+        let m = VecMat::parse_bytes([
+            ".......S.......",
+            "...............",
+            ".......^.......",
+            "...............",
+            "......^.^......",
+            "...............",
+            ".....^.^.^.....",
+            "...............",
+            "....^.^...^....",
+            "...............",
+            "...^.^...^.^...",
+            "...............",
+            "..^...^.....^..",
+            "...............",
+            ".^.^.^.^.^...^.",
+            "...............",
+        ])
+        .unwrap();
+        let start = m.find(&b'S').unwrap();
+        let split = |_, &c: &u8, _| match c {
+            b'^' => vec![Dir::SW, Dir::SE],
+            _ => vec![Dir::S],
+        };
+        let e = m.beams(start, Dir::S, split);
+        let splits = m.iter_pos().filter(|&(p, &c)| c == b'^' && e.contains(p));
+        assert_eq!(splits.count(), 21);
+        let t = m.beam_timelines(start, Dir::S, split).unwrap();
+        assert_eq!(t.total, 40);
+        assert_eq!(t.reaching[start], 1);
+        assert_eq!(
+            t.reaching
+                .get_row(m.rows() - 1)
+                .unwrap()
+                .iter()
+                .sum::<usize>(),
+            40
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use core::fmt;
+use std::{
+    collections::HashSet,
+    ops::{BitAndAssign, BitOrAssign, BitXorAssign, Not},
+};
+
+use super::{Dir, Point, VecMat};
+
+const BITS: usize = u64::BITS as usize;
+
+/// A grid of booleans packed as one bit per cell; each row is stored in
+/// whole u64 words (cell x of a row is bit x % 64 of word x / 64), the
+/// unused high bits of the last word of a row are always 0
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    rows: usize,
+    cols: usize,
+    words_per_row: usize,
+}
+
+impl BitGrid {
+    /// A grid of the given (rows, cols) shape with every cell unset
+    pub fn new(shape: (usize, usize)) -> Self {
+        let (rows, cols) = shape;
+        let words_per_row = cols.div_ceil(BITS);
+        Self {
+            words: vec![0; rows * words_per_row],
+            rows,
+            cols,
+            words_per_row,
+        }
+    }
+
+    /// A grid with the given cells set, ignoring the ones out of bounds
+    pub fn from_points(
+        shape: (usize, usize),
+        points: impl IntoIterator<Item = Point<usize>>,
+    ) -> Self {
+        let mut grid = Self::new(shape);
+        for p in points {
+            if let Some((w, mask)) = grid.locate(p) {
+                grid.words[w] |= mask;
+            }
+        }
+        grid
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// The index of the word holding p and the mask of p's bit in it
+    fn locate(&self, p: Point<usize>) -> Option<(usize, u64)> {
+        (p.x < self.cols && p.y < self.rows)
+            .then(|| (p.y * self.words_per_row + p.x / BITS, 1 << (p.x % BITS)))
+    }
+
+    /// Mask of the bits of the last word of a row which are in the grid
+    fn last_word_mask(&self) -> u64 {
+        match self.cols % BITS {
+            0 => !0,
+            n => (1 << n) - 1,
+        }
+    }
+
+    /// Clear the unused bits at the end of every row
+    fn clear_padding(&mut self) {
+        let mask = self.last_word_mask();
+        if self.words_per_row > 0 {
+            for row in self.words.chunks_exact_mut(self.words_per_row) {
+                *row.last_mut().unwrap() &= mask;
+            }
+        }
+    }
+
+    pub fn get(&self, p: Point<usize>) -> Option<bool> {
+        let (w, mask) = self.locate(p)?;
+        Some(self.words[w] & mask != 0)
+    }
+
+    /// Set the cell to `val`, returning its previous value
+    pub fn set(&mut self, p: Point<usize>, val: bool) -> bool {
+        let (w, mask) = self.locate(p).expect("should be in bounds");
+        let old = self.words[w] & mask != 0;
+        if val {
+            self.words[w] |= mask;
+        } else {
+            self.words[w] &= !mask;
+        }
+        old
+    }
+
+    /// Flip the cell, returning its new value
+    pub fn toggle(&mut self, p: Point<usize>) -> bool {
+        let (w, mask) = self.locate(p).expect("should be in bounds");
+        self.words[w] ^= mask;
+        self.words[w] & mask != 0
+    }
+
+    /// The words storing row y
+    pub fn row_words(&self, y: usize) -> &[u64] {
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// Replace row y with `op(row y, row other_y of other)` word by word,
+    /// eg `grid.combine_row(y, &walls, y, |a, b| a & !b)`; the grids must
+    /// have the same number of columns
+    pub fn combine_row(
+        &mut self,
+        y: usize,
+        other: &BitGrid,
+        other_y: usize,
+        op: impl Fn(u64, u64) -> u64,
+    ) {
+        assert_eq!(self.cols, other.cols, "rows must have the same length");
+        let (start, mask) = (y * self.words_per_row, self.last_word_mask());
+        let row = &mut self.words[start..start + self.words_per_row];
+        for (dst, &src) in row.iter_mut().zip(other.row_words(other_y)) {
+            *dst = op(*dst, src);
+        }
+        if let Some(last) = row.last_mut() {
+            *last &= mask;
+        }
+    }
+
+    /// Number of set cells
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Number of set cells in row y
+    pub fn row_count_ones(&self, y: usize) -> usize {
+        self.row_words(y)
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// The set cells in row-major order
+    pub fn iter_ones(&self) -> impl Iterator<Item = Point<usize>> + '_ {
+        let wpr = self.words_per_row;
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let (y, x0) = (i / wpr, (i % wpr) * BITS);
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                (rest != 0).then(|| {
+                    let bit = rest.trailing_zeros() as usize;
+                    rest &= rest - 1;
+                    Point::new(x0 + bit, y)
+                })
+            })
+        })
+    }
+
+    /// The grid with every set cell moved one step in direction `dir`,
+    /// those moving out of the grid being lost
+    pub fn shifted(&self, dir: Dir) -> Self {
+        let (dx, dy) = dir.to_i8_offset();
+        let mut out = Self::new(self.shape());
+        let wpr = self.words_per_row;
+        for y in 0..self.rows {
+            let Some(src) = y
+                .checked_add_signed(-dy as isize)
+                .filter(|&s| s < self.rows)
+            else {
+                continue;
+            };
+            let (src, dst) = (self.row_words(src), &mut out.words[y * wpr..(y + 1) * wpr]);
+            for (i, d) in dst.iter_mut().enumerate() {
+                *d = match dx {
+                    // moving right, bits go up and carry into the next word
+                    1 => src[i] << 1 | i.checked_sub(1).map_or(0, |j| src[j] >> (BITS - 1)),
+                    -1 => src[i] >> 1 | src.get(i + 1).map_or(0, |w| w << (BITS - 1)),
+                    _ => src[i],
+                };
+            }
+        }
+        out.clear_padding();
+        out
+    }
+
+    /// The cells that are set or have a set neighbour in one of `dirs`,
+    /// eg the next frontier of a breadth-first search
+    pub fn spread(&self, dirs: &[Dir]) -> Self {
+        let mut out = self.clone();
+        for &dir in dirs {
+            out |= &self.shifted(dir);
+        }
+        out
+    }
+
+    pub fn to_vecmat(&self) -> VecMat<bool> {
+        VecMat::filled_with(self.shape(), |p| self.get(p) == Some(true))
+    }
+
+    pub fn to_hashset(&self) -> HashSet<Point<usize>> {
+        self.iter_ones().collect()
+    }
+}
+
+impl From<&VecMat<bool>> for BitGrid {
+    fn from(grid: &VecMat<bool>) -> Self {
+        let ones = grid.iter_pos().filter(|(_, b)| **b).map(|(p, _)| p);
+        Self::from_points(grid.shape(), ones)
+    }
+}
+
+impl BitAndAssign<&BitGrid> for BitGrid {
+    fn bitand_assign(&mut self, rhs: &BitGrid) {
+        assert_eq!(self.shape(), rhs.shape(), "grids must have the same shape");
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(a, b)| *a &= b);
+    }
+}
+
+impl BitOrAssign<&BitGrid> for BitGrid {
+    fn bitor_assign(&mut self, rhs: &BitGrid) {
+        assert_eq!(self.shape(), rhs.shape(), "grids must have the same shape");
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(a, b)| *a |= b);
+    }
+}
+
+impl BitXorAssign<&BitGrid> for BitGrid {
+    fn bitxor_assign(&mut self, rhs: &BitGrid) {
+        assert_eq!(self.shape(), rhs.shape(), "grids must have the same shape");
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(a, b)| *a ^= b);
+    }
+}
+
+impl Not for BitGrid {
+    type Output = BitGrid;
+
+    fn not(mut self) -> Self::Output {
+        self.words.iter_mut().for_each(|w| *w = !*w);
+        self.clear_padding();
+        self
+    }
+}
+
+impl fmt::Display for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_vecmat()
+            .formatter_with(|_, _, &b| if b { '#' } else { '.' })
+            .fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bits() {
+        let mut g = BitGrid::new((3, 70));
+        g.set(Point::new(63, 1), true);
+        assert!(g.toggle(Point::new(69, 2)));
+        assert!(!g.toggle(Point::new(69, 2)));
+        g.set(Point::new(0, 0), true);
+        assert_eq!(g.get(Point::new(63, 1)), Some(true));
+        assert_eq!(g.get(Point::new(70, 1)), None);
+        assert_eq!(g.count_ones(), 2);
+        let e = g.shifted(Dir::SE);
+        assert_eq!(
+            e.iter_ones().collect::<Vec<_>>(),
+            [Point::new(1, 1), Point::new(64, 2)]
+        );
+        let back = e.shifted(Dir::NW).shifted(Dir::NW);
+        assert_eq!(back.to_hashset(), HashSet::from([Point::new(62, 0)]));
+        assert_eq!(g.shifted(Dir::W).count_ones(), 1);
+        let all = !BitGrid::new((3, 70));
+        assert_eq!(all.count_ones(), 210);
+        assert_eq!(all.shifted(Dir::E).row_count_ones(0), 69);
+        let mut x = all.clone();
+        x ^= &g;
+        assert_eq!(x.count_ones(), 208);
+        x.combine_row(1, &g, 1, |a, b| a | b);
+        assert_eq!(x.row_count_ones(1), 70);
+    }
+
+    #[test]
+    fn frontier() {
+        let maze = VecMat::parse_bytes(["..#..", ".##.#", "....."]).unwrap();
+        let walls = BitGrid::from(&VecMat::filled_with(maze.shape(), |p| maze[p] == b'#'));
+        assert_eq!(walls.to_string(), "..#..\n.##.#\n.....\n");
+        let open = !walls;
+        let mut reached = BitGrid::from_points(maze.shape(), [Point::new(0, 0)]);
+        let mut steps = 0;
+        while reached.get(Point::new(4, 0)) == Some(false) {
+            reached = reached.spread(&Dir::CROSS);
+            reached &= &open;
+            steps += 1;
+        }
+        assert_eq!(steps, 8);
+        assert_eq!(BitGrid::from(&reached.to_vecmat()), reached);
+    }
+}
@@ -0,0 +1,200 @@
+use super::{Coord, Point, VecMat};
+
+/// The real-world extent of a cell of a compressed grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompressedCell<T> {
+    /// The real top left corner of the cell
+    pub origin: Point<T>,
+    pub width: T,
+    pub height: T,
+}
+
+impl<T: Coord> CompressedCell<T> {
+    /// Number of real points covered by the cell
+    pub fn area(&self) -> T {
+        self.width * self.height
+    }
+
+    /// Whether the cell stands for no real points at all
+    /// (a gap between two adjacent coordinates)
+    pub fn is_empty(&self) -> bool {
+        self.width == T::ZERO || self.height == T::ZERO
+    }
+}
+
+/// Coordinate compression of a set of points: every distinct x (or y)
+/// coordinate gets a row (or column) of its own at an even index, and the
+/// gap between two consecutive ones is squashed into the odd index in between
+/// (which may be empty, if the coordinates are adjacent)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compression<T> {
+    xs: Vec<T>,
+    ys: Vec<T>,
+}
+
+/// Compressed index of a coordinate in the sorted table, if in range
+fn compress_axis<T: Ord>(table: &[T], v: T) -> Option<usize> {
+    match table.binary_search(&v) {
+        Ok(i) => Some(2 * i),
+        Err(i) if i > 0 && i < table.len() => Some(2 * i - 1),
+        Err(_) => None,
+    }
+}
+
+/// Real start and extent of the compressed index
+fn decompress_axis<T: Coord>(table: &[T], i: usize) -> Option<(T, T)> {
+    if i.is_multiple_of(2) {
+        table.get(i / 2).map(|&v| (v, T::ONE))
+    } else {
+        let (&lo, &hi) = (table.get(i / 2)?, table.get(i / 2 + 1)?);
+        Some((lo + T::ONE, hi - lo - T::ONE))
+    }
+}
+
+impl<T: Coord> Compression<T> {
+    pub fn new(points: impl IntoIterator<Item = Point<T>>) -> Self {
+        let (mut xs, mut ys): (Vec<_>, Vec<_>) = points.into_iter().map(|p| (p.x, p.y)).unzip();
+        for table in [&mut xs, &mut ys] {
+            table.sort_unstable();
+            table.dedup();
+        }
+        Self { xs, ys }
+    }
+
+    /// The sorted distinct x coordinates
+    pub fn xs(&self) -> &[T] {
+        &self.xs
+    }
+
+    /// The sorted distinct y coordinates
+    pub fn ys(&self) -> &[T] {
+        &self.ys
+    }
+
+    /// The (rows, cols) of the compressed grid
+    pub fn shape(&self) -> (usize, usize) {
+        let len = |t: &[T]| (2 * t.len()).saturating_sub(1);
+        (len(&self.ys), len(&self.xs))
+    }
+
+    /// The compressed cell containing the real point, if it's within the
+    /// range of the coordinates
+    pub fn compress(&self, p: Point<T>) -> Option<Point<usize>> {
+        Some(Point::new(
+            compress_axis(&self.xs, p.x)?,
+            compress_axis(&self.ys, p.y)?,
+        ))
+    }
+
+    /// The real extent of a compressed cell
+    pub fn cell(&self, p: Point<usize>) -> Option<CompressedCell<T>> {
+        let (x, width) = decompress_axis(&self.xs, p.x)?;
+        let (y, height) = decompress_axis(&self.ys, p.y)?;
+        Some(CompressedCell {
+            origin: Point::new(x, y),
+            width,
+            height,
+        })
+    }
+
+    /// The real top left corner of a compressed cell
+    pub fn decompress(&self, p: Point<usize>) -> Option<Point<T>> {
+        self.cell(p).map(|c| c.origin)
+    }
+
+    /// The compressed corners (top left, bottom right) of the rectangle
+    /// with the given opposite real corners
+    pub fn compress_rect(&self, a: Point<T>, b: Point<T>) -> Option<(Point<usize>, Point<usize>)> {
+        let min = Point::new(a.x.min(b.x), a.y.min(b.y));
+        let max = Point::new(a.x.max(b.x), a.y.max(b.y));
+        Some((self.compress(min)?, self.compress(max)?))
+    }
+
+    /// The real corners (top left, bottom right, both included) covered by
+    /// the compressed rectangle with the given opposite corners
+    pub fn decompress_rect(
+        &self,
+        a: Point<usize>,
+        b: Point<usize>,
+    ) -> Option<(Point<T>, Point<T>)> {
+        let min = self.cell(Point::new(a.x.min(b.x), a.y.min(b.y)))?;
+        let max = self.cell(Point::new(a.x.max(b.x), a.y.max(b.y)))?;
+        let far = Point::new(
+            max.origin.x + max.width - T::ONE,
+            max.origin.y + max.height - T::ONE,
+        );
+        Some((min.origin, far))
+    }
+
+    /// The compressed grid, each cell holding its real extent
+    pub fn cells(&self) -> VecMat<CompressedCell<T>> {
+        self.grid_with(|_, c| c)
+    }
+
+    /// A compressed grid with values computed from each cell's position
+    /// and real extent
+    pub fn grid_with<U>(&self, f: impl Fn(Point<usize>, CompressedCell<T>) -> U) -> VecMat<U> {
+        VecMat::filled_with(self.shape(), |p| {
+            f(p, self.cell(p).expect("cells of the grid are in range"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Dir, polygon};
+
+    #[test]
+    fn mapping() {
+        let c = Compression::new([
+            Point::new(10usize, 5),
+            Point::new(100, 6),
+            Point::new(11, 50),
+        ]);
+        assert_eq!(c.xs(), [10, 11, 100]);
+        assert_eq!(c.shape(), (5, 5));
+        assert_eq!(c.compress(Point::new(50, 6)), Some(Point::new(3, 2)));
+        assert_eq!(c.compress(Point::new(9, 6)), None);
+        let gap = c.cell(Point::new(3, 3)).unwrap();
+        assert_eq!(
+            (gap.origin, gap.width, gap.height),
+            (Point::new(12, 7), 88, 43)
+        );
+        assert!(c.cell(Point::new(1, 0)).unwrap().is_empty());
+        let rect = c
+            .compress_rect(Point::new(100, 5), Point::new(11, 50))
+            .unwrap();
+        assert_eq!(rect, (Point::new(2, 0), Point::new(4, 4)));
+        assert_eq!(
+            c.decompress_rect(rect.0, rect.1),
+            Some((Point::new(11, 5), Point::new(100, 50)))
+        );
+        let total: usize = c.cells().iter_all().map(|c| c.area()).sum();
+        assert_eq!(total, 91 * 46);
+    }
+
+    #[test]
+    fn lagoon_area() {
+        let lagoon = [
+            (0, 0),
+            (600, 0),
+            (600, 500),
+            (400, 500),
+            (400, 700),
+            (0, 700),
+        ]
+        .map(|(x, y)| Point::new(x, y));
+        let c = Compression::new(lagoon);
+        let cells = c.cells();
+        let inside = |cell: &CompressedCell<isize>| polygon::contains(&lagoon, cell.origin);
+        let area: isize = cells
+            .iter_all()
+            .filter(|c| inside(c))
+            .map(|c| c.area())
+            .sum();
+        assert_eq!(area, polygon::lattice_points(&lagoon));
+        let outside = cells.flood_fill([Point::new(4, 4)], &Dir::CROSS, |_, c| !inside(c));
+        assert_eq!(outside.reached.len(), 4);
+    }
+}
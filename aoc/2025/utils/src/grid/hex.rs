@@ -0,0 +1,232 @@
+//! Hexagonal grids with "pointy top" hexagons, so that they're arranged
+//! in horizontal rows (each one shifted by half a cell from the previous);
+//! positions use axial coordinates `q` (along rows) and `r` (along the
+//! south-east axis), the implied third cube coordinate being `s = -q - r`.
+
+use std::fmt::Display;
+
+use super::{Delta, Point};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexDir {
+    NE,
+    E,
+    SE,
+    SW,
+    W,
+    NW,
+}
+
+impl HexDir {
+    pub const ALL: [HexDir; 6] = [
+        HexDir::NE,
+        HexDir::E,
+        HexDir::SE,
+        HexDir::SW,
+        HexDir::W,
+        HexDir::NW,
+    ];
+
+    pub fn id(&self) -> u8 {
+        match self {
+            HexDir::NE => 0,
+            HexDir::E => 1,
+            HexDir::SE => 2,
+            HexDir::SW => 3,
+            HexDir::W => 4,
+            HexDir::NW => 5,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.get(id as usize).copied()
+    }
+
+    /// The (dq, dr) axial offset of a step in this direction
+    pub fn to_i8_offset(&self) -> (i8, i8) {
+        match self {
+            HexDir::NE => (1, -1),
+            HexDir::E => (1, 0),
+            HexDir::SE => (0, 1),
+            HexDir::SW => (-1, 1),
+            HexDir::W => (-1, 0),
+            HexDir::NW => (0, -1),
+        }
+    }
+
+    /// The axial offset of a step in this direction, as (dq, dr) in a Delta
+    pub fn to_delta<T: From<i8>>(&self) -> Delta<T> {
+        let (dq, dr) = self.to_i8_offset();
+        Delta::new(dq.into(), dr.into())
+    }
+
+    /// Rotate by n sixths of a turn clockwise
+    pub fn rotate_by(&self, n: isize) -> Self {
+        let id = (self.id() as isize + n).rem_euclid(6) as u8;
+        Self::from_id(id).unwrap()
+    }
+
+    pub fn clockwise(&self) -> Self {
+        self.rotate_by(1)
+    }
+
+    pub fn counterclockwise(&self) -> Self {
+        self.rotate_by(-1)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate_by(3)
+    }
+}
+
+impl Display for HexDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            HexDir::NE => '↗',
+            HexDir::E => '→',
+            HexDir::SE => '↘',
+            HexDir::SW => '↙',
+            HexDir::W => '←',
+            HexDir::NW => '↖',
+        };
+        write!(f, "{}", c)
+    }
+}
+
+/// A hexagon's position in axial coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hex {
+    pub q: isize,
+    pub r: isize,
+}
+
+impl Hex {
+    pub const fn new(q: isize, r: isize) -> Self {
+        Self { q, r }
+    }
+
+    /// The third cube coordinate
+    pub fn s(&self) -> isize {
+        -self.q - self.r
+    }
+
+    pub fn add(&self, d: Delta<isize>) -> Self {
+        Self::new(self.q + d.dx, self.r + d.dy)
+    }
+
+    pub fn neighbour(&self, dir: HexDir) -> Self {
+        self.add(dir.to_delta())
+    }
+
+    pub fn neighbours(&self) -> [Self; 6] {
+        HexDir::ALL.map(|dir| self.neighbour(dir))
+    }
+
+    /// Number of steps needed to go from one hex to the other
+    pub fn distance(&self, other: Self) -> usize {
+        let dq = self.q.abs_diff(other.q);
+        let dr = self.r.abs_diff(other.r);
+        let ds = self.s().abs_diff(other.s());
+        (dq + dr + ds) / 2
+    }
+
+    /// The hexes at exactly `radius` steps, clockwise from the one due W
+    pub fn ring(&self, radius: usize) -> impl Iterator<Item = Self> + use<> {
+        let start = self.add(HexDir::W.to_delta::<isize>().scale(radius as isize));
+        let steps = HexDir::ALL
+            .into_iter()
+            .flat_map(move |dir| std::iter::repeat_n(dir, radius));
+        let center = (radius == 0).then_some(*self);
+        center.into_iter().chain(steps.scan(start, |hex, dir| {
+            let cur = *hex;
+            *hex = hex.neighbour(dir);
+            Some(cur)
+        }))
+    }
+
+    /// The hexes at most `radius` steps away, ring by ring from the center
+    pub fn spiral(&self, radius: usize) -> impl Iterator<Item = Self> + use<> {
+        let center = *self;
+        (0..=radius).flat_map(move |r| center.ring(r))
+    }
+
+    /// Position in "odd-r" offset coordinates, ie rows of hexes where odd
+    /// rows are shifted half a hex to the right; suitable to store in a
+    /// [`super::VecMat`]
+    pub fn to_offset(&self) -> Point<isize> {
+        Point::new(self.q + (self.r - (self.r & 1)) / 2, self.r)
+    }
+
+    /// Inverse of [`Hex::to_offset`]
+    pub fn from_offset(p: Point<isize>) -> Self {
+        Self::new(p.x - (p.y - (p.y & 1)) / 2, p.y)
+    }
+
+    /// Position in a [`super::VecMat`] with the given (rows, cols) storing
+    /// hexes in "odd-r" offset coordinates, if it's in bounds
+    pub fn to_grid(&self, (rows, cols): (usize, usize)) -> Option<Point<usize>> {
+        let Point { x, y } = self.to_offset();
+        Point::new(x.try_into().ok()?, y.try_into().ok()?).as_in_bounds((cols, rows))
+    }
+
+    /// The hex stored at the given position of a [`super::VecMat`]
+    /// in "odd-r" offset coordinates
+    pub fn from_grid(p: Point<usize>) -> Self {
+        Self::from_offset(Point::new(p.x as isize, p.y as isize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directions() {
+        for dir in HexDir::ALL {
+            assert_eq!(dir.opposite().opposite(), dir);
+            assert_eq!(dir.clockwise().counterclockwise(), dir);
+            let there = Hex::new(0, 0).neighbour(dir).neighbour(dir.opposite());
+            assert_eq!(there, Hex::new(0, 0));
+        }
+        assert_eq!(HexDir::NW.clockwise(), HexDir::NE);
+        assert_eq!(HexDir::E.to_string(), "→");
+    }
+
+    #[test]
+    fn rings() {
+        let c = Hex::new(2, -1);
+        assert_eq!(c.ring(0).collect::<Vec<_>>(), [c]);
+        for radius in 1..4 {
+            let ring: Vec<_> = c.ring(radius).collect();
+            assert_eq!(ring.len(), 6 * radius);
+            assert!(ring.iter().all(|h| h.distance(c) == radius));
+        }
+        assert_eq!(c.spiral(2).count(), 19);
+        let mut around: Vec<_> = c.ring(1).collect();
+        let mut neighbours = c.neighbours().to_vec();
+        around.sort_by_key(|h| (h.q, h.r));
+        neighbours.sort_by_key(|h| (h.q, h.r));
+        assert_eq!(around, neighbours);
+    }
+
+    #[test]
+    fn offsets() {
+        for h in Hex::new(0, 0).spiral(3) {
+            assert_eq!(Hex::from_offset(h.to_offset()), h);
+        }
+        // in odd-r, the SE neighbour of an even row stays in the same column
+        assert_eq!(
+            Hex::from_grid(Point::new(1, 0))
+                .neighbour(HexDir::SE)
+                .to_grid((2, 2)),
+            Some(Point::new(1, 1))
+        );
+        assert_eq!(
+            Hex::from_grid(Point::new(1, 1))
+                .neighbour(HexDir::SE)
+                .to_grid((3, 3)),
+            Some(Point::new(2, 2))
+        );
+        assert_eq!(Hex::new(0, 0).neighbour(HexDir::W).to_grid((3, 3)), None);
+    }
+}
@@ -0,0 +1,264 @@
+//! Export grids as images, eg to look for a picture in thousands of
+//! simulation steps with an image viewer rather than in the terminal.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use super::{Point, VecMat};
+
+/// A pixel colour as [red, green, blue]
+pub type Rgb = [u8; 3];
+
+/// An image file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    /// Binary PPM (P6), trivial but not supported by every viewer
+    Ppm,
+    /// Uncompressed PNG
+    Png,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+        }
+    }
+
+    /// The format for the path's extension (png, anything else is ppm)
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("png") => ImageFormat::Png,
+            _ => ImageFormat::Ppm,
+        }
+    }
+}
+
+/// An RGB image, see [`VecMat::to_image`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    /// Row-major pixels, 3 bytes each
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Rgb> {
+        let i = (x < self.width && y < self.height).then(|| 3 * (y * self.width + x))?;
+        Some([self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]])
+    }
+
+    pub fn write_ppm(&self, mut w: impl Write) -> io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", self.width, self.height)?;
+        w.write_all(&self.pixels)
+    }
+
+    pub fn write_png(&self, mut w: impl Write) -> io::Result<()> {
+        w.write_all(b"\x89PNG\r\n\x1a\n")?;
+        let mut header = Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, truecolour, no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+        png_chunk(&mut w, b"IHDR", &header)?;
+        // every scanline starts with its filter type (0 is none)
+        let scanlines: Vec<u8> = self
+            .pixels
+            .chunks(3 * self.width.max(1))
+            .flat_map(|row| std::iter::once(0).chain(row.iter().copied()))
+            .collect();
+        png_chunk(&mut w, b"IDAT", &zlib_stored(&scanlines))?;
+        png_chunk(&mut w, b"IEND", &[])
+    }
+
+    pub fn write(&self, w: impl Write, format: ImageFormat) -> io::Result<()> {
+        match format {
+            ImageFormat::Ppm => self.write_ppm(w),
+            ImageFormat::Png => self.write_png(w),
+        }
+    }
+
+    /// Write the image to a file, in the given format
+    pub fn save_as(&self, path: impl AsRef<Path>, format: ImageFormat) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write(&mut w, format)?;
+        w.flush()
+    }
+
+    /// Write the image to a file, in the format given by its extension
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let format = ImageFormat::for_path(path.as_ref());
+        self.save_as(path, format)
+    }
+}
+
+fn png_chunk(mut w: impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    w.write_all(&(data.len() as u32).to_be_bytes())?;
+    w.write_all(kind)?;
+    w.write_all(data)?;
+    let crc = crc32(kind.iter().chain(data));
+    w.write_all(&crc.to_be_bytes())
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let crc = bytes.into_iter().fold(!0u32, |mut crc, &b| {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg());
+        }
+        crc
+    });
+    !crc
+}
+
+/// A zlib stream holding `data` in uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        out.push(blocks.peek().is_none() as u8);
+        let len = block.len() as u16;
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), &x| {
+        let a = (a + x as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    out.extend(((b << 16) | a).to_be_bytes());
+    out
+}
+
+impl<T> VecMat<T> {
+    /// An image of the grid with each cell as a `scale`×`scale` square
+    /// of colour `colour(point, cell)`
+    pub fn to_image(&self, scale: usize, colour: impl Fn(Point<usize>, &T) -> Rgb) -> Image {
+        let (width, height) = (self.cols() * scale, self.rows() * scale);
+        let mut pixels = Vec::with_capacity(3 * width * height);
+        for (y, row) in self.iter_rows().enumerate() {
+            let line: Vec<u8> = row
+                .iter()
+                .enumerate()
+                .flat_map(|(x, c)| colour(Point::new(x, y), c).repeat(scale))
+                .collect();
+            for _ in 0..scale {
+                pixels.extend(&line);
+            }
+        }
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Saves a numbered sequence of images in a directory,
+/// as `{prefix}{number:06}.{extension}`
+#[derive(Debug, Clone)]
+pub struct Frames {
+    dir: PathBuf,
+    prefix: String,
+    format: ImageFormat,
+    next: usize,
+}
+
+impl Frames {
+    /// Save frames into `dir`, creating it if needed
+    pub fn new(dir: impl Into<PathBuf>, prefix: &str, format: ImageFormat) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            prefix: prefix.to_string(),
+            format,
+            next: 0,
+        })
+    }
+
+    /// Start numbering from `n` rather than 0, eg to number frames by step
+    pub fn starting_at(self, n: usize) -> Self {
+        Self { next: n, ..self }
+    }
+
+    /// The path the next frame will be saved to
+    pub fn next_path(&self) -> PathBuf {
+        let name = format!(
+            "{}{:06}.{}",
+            self.prefix,
+            self.next,
+            self.format.extension()
+        );
+        self.dir.join(name)
+    }
+
+    /// Save the next frame, returning its path
+    pub fn save(&mut self, image: &Image) -> io::Result<PathBuf> {
+        let path = self.next_path();
+        image.save_as(&path, self.format)?;
+        self.next += 1;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checker() -> Image {
+        let m = VecMat::filled_with((2, 3), |p| (p.x + p.y) % 2 == 0);
+        m.to_image(2, |_, &on| if on { [255, 0, 0] } else { [0, 0, 9] })
+    }
+
+    #[test]
+    fn ppm() {
+        let img = checker();
+        assert_eq!((img.width(), img.height()), (6, 4));
+        assert_eq!(img.pixel(1, 1), Some([255, 0, 0]));
+        assert_eq!(img.pixel(2, 1), Some([0, 0, 9]));
+        assert_eq!(img.pixel(3, 3), Some([255, 0, 0]));
+        assert_eq!(img.pixel(6, 0), None);
+        let mut out = Vec::new();
+        img.write_ppm(&mut out).unwrap();
+        assert!(out.starts_with(b"P6\n6 4\n255\n\xff\x00\x00\xff\x00\x00\x00\x00\x09"));
+        assert_eq!(out.len(), 11 + 6 * 4 * 3);
+    }
+
+    #[test]
+    fn png() {
+        assert_eq!(crc32(b"IEND"), 0xae426082);
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+        let mut out = Vec::new();
+        checker().write_png(&mut out).unwrap();
+        assert!(out.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x06\0\0\0\x04\x08\x02"));
+        assert!(out.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+        // zlib header, one final stored block of 4 scanlines, then the checksum
+        let idat = 8 + 25 + 8;
+        let len = 4 * (1 + 6 * 3);
+        assert_eq!(
+            &out[idat..idat + 7],
+            [0x78, 0x01, 1, len as u8, 0, !len as u8, 0xff]
+        );
+        assert_eq!(
+            zlib_stored(b"Wikipedia")[2 + 5 + 9..],
+            0x11e60398u32.to_be_bytes()
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use std::iter;
+
+use super::{Delta, Dir, Point, VecMat};
+use crate::maths::gcd;
+
+impl Delta<isize> {
+    /// The smallest step in the same direction with integer coordinates,
+    /// ie the delta divided by the gcd of its components (zero stays zero)
+    pub fn reduced(&self) -> Self {
+        match gcd(self.dx, self.dy) {
+            0 => *self,
+            g => Delta::new(self.dx / g, self.dy / g),
+        }
+    }
+}
+
+/// The (inclusive) range of k such that `0 <= start + k * step < len`
+fn steps_within(start: isize, step: isize, len: usize) -> (isize, isize) {
+    let len = len as isize;
+    if step == 0 {
+        return if (0..len).contains(&start) {
+            (isize::MIN, isize::MAX)
+        } else {
+            (1, 0)
+        };
+    }
+    // k * step must be in [lo, hi]
+    let (mut lo, mut hi, mut step) = (-start, len - 1 - start, step);
+    if step < 0 {
+        (lo, hi, step) = (-hi, -lo, -step);
+    }
+    (-(-lo).div_euclid(step), hi.div_euclid(step))
+}
+
+/// All the lattice points on the segment from `a` to `b`, both included
+pub fn lattice_points(a: Point<isize>, b: Point<isize>) -> impl Iterator<Item = Point<isize>> {
+    let delta = b - a;
+    let step = delta.reduced();
+    let count = if step.dx != 0 {
+        delta.dx / step.dx
+    } else if step.dy != 0 {
+        delta.dy / step.dy
+    } else {
+        0
+    };
+    (0..=count).map(move |k| a + step * k)
+}
+
+/// The cells approximating the segment from `a` to `b` (both included)
+/// with Bresenham's algorithm, each cell touching the previous one
+/// horizontally, vertically or diagonally
+pub fn bresenham(a: Point<isize>, b: Point<isize>) -> impl Iterator<Item = Point<isize>> {
+    let (dx, dy) = ((b.x - a.x).abs(), -(b.y - a.y).abs());
+    let (sx, sy) = ((b.x - a.x).signum(), (b.y - a.y).signum());
+    let mut err = dx + dy;
+    let mut p = Some(a);
+    iter::from_fn(move || {
+        let cur = p?;
+        p = (cur != b).then(|| {
+            let (mut next, e2) = (cur, 2 * err);
+            if e2 >= dy {
+                err += dy;
+                next.x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                next.y += sy;
+            }
+            next
+        });
+        Some(cur)
+    })
+}
+
+impl<T> VecMat<T> {
+    /// The cells from `from` (included) going in direction `dir`
+    /// until the edge of the grid
+    pub fn ray(&self, from: Point<usize>, dir: Dir) -> impl Iterator<Item = (Point<usize>, &T)> {
+        let delta = dir.to_delta();
+        let start = self.get(from).map(|c| (from, c));
+        iter::successors(start, move |(p, _)| {
+            let next = p.checked_add_signed(delta)?;
+            Some((next, self.get(next)?))
+        })
+    }
+
+    /// The cells from `from` (included) going in direction `dir`
+    /// until the edge of the grid or just before the first cell where `stop` holds
+    pub fn ray_until(
+        &self,
+        from: Point<usize>,
+        dir: Dir,
+        mut stop: impl FnMut(Point<usize>, &T) -> bool,
+    ) -> impl Iterator<Item = (Point<usize>, &T)> {
+        self.ray(from, dir).take_while(move |&(p, c)| !stop(p, c))
+    }
+
+    /// The cells of row `y`, left to right
+    pub fn row(&self, y: usize) -> impl Iterator<Item = (Point<usize>, &T)> {
+        self.ray(Point::new(0, y), Dir::E)
+    }
+
+    /// The cells of column `x`, top to bottom
+    pub fn column(&self, x: usize) -> impl Iterator<Item = (Point<usize>, &T)> {
+        self.ray(Point::new(x, 0), Dir::S)
+    }
+
+    /// The cells of the diagonal going through `p` from top left to bottom right
+    pub fn diagonal(&self, p: Point<usize>) -> impl Iterator<Item = (Point<usize>, &T)> {
+        let back = p.x.min(p.y);
+        self.ray(Point::new(p.x - back, p.y - back), Dir::SE)
+    }
+
+    /// The cells of the anti-diagonal going through `p` from top right to bottom left
+    pub fn anti_diagonal(&self, p: Point<usize>) -> impl Iterator<Item = (Point<usize>, &T)> {
+        // how far we can go up-right before leaving the grid
+        let back = p.y.min(self.cols().saturating_sub(p.x + 1));
+        self.ray(Point::new(p.x + back, p.y - back), Dir::SW)
+    }
+
+    /// The lattice points of the whole line through `a` and `b` which are in
+    /// the grid, in order from `a` towards `b`; `a` and `b` may be outside the grid
+    pub fn line_through(
+        &self,
+        a: Point<isize>,
+        b: Point<isize>,
+    ) -> impl Iterator<Item = Point<usize>> + use<T> {
+        assert_ne!(a, b, "a line needs two distinct points");
+        let step = (b - a).reduced();
+        let (lo_x, hi_x) = steps_within(a.x, step.dx, self.cols());
+        let (lo_y, hi_y) = steps_within(a.y, step.dy, self.rows());
+        (lo_x.max(lo_y)..=hi_x.min(hi_y)).map(move |k| {
+            let p = a + step * k;
+            Point::new(p.x as usize, p.y as usize)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letters<'a>(it: impl Iterator<Item = (Point<usize>, &'a u8)>) -> String {
+        it.map(|(_, &c)| c as char).collect()
+    }
+
+    #[test]
+    fn lines() {
+        let m = VecMat::parse_bytes(["abcd", "efgh", "ijkl"]).unwrap();
+        assert_eq!(letters(m.row(1)), "efgh");
+        assert_eq!(letters(m.column(2)), "cgk");
+        assert_eq!(letters(m.diagonal(Point::new(3, 2))), "bgl");
+        assert_eq!(letters(m.diagonal(Point::new(0, 1))), "ej");
+        assert_eq!(letters(m.anti_diagonal(Point::new(1, 2))), "dgj");
+        assert_eq!(letters(m.anti_diagonal(Point::new(0, 0))), "a");
+        assert_eq!(letters(m.ray(Point::new(3, 2), Dir::NW)), "lgb");
+        assert_eq!(letters(m.ray(Point::new(4, 0), Dir::W)), "");
+    }
+
+    #[test]
+    fn segments() {
+        let p = |x, y| Point::new(x, y);
+        let pts: Vec<_> = lattice_points(p(1, 2), p(7, -7)).collect();
+        assert_eq!(pts, [p(1, 2), p(3, -1), p(5, -4), p(7, -7)]);
+        assert_eq!(
+            lattice_points(p(3, 3), p(3, 3)).collect::<Vec<_>>(),
+            [p(3, 3)]
+        );
+        let line: Vec<_> = bresenham(p(0, 0), p(5, 2)).collect();
+        assert_eq!(line, [p(0, 0), p(1, 0), p(2, 1), p(3, 1), p(4, 2), p(5, 2)]);
+        let back: Vec<_> = bresenham(p(5, 2), p(0, 0)).collect();
+        assert_eq!(back.len(), 6);
+        assert_eq!(bresenham(p(2, 0), p(2, -3)).count(), 4);
+    }
+
+    #[test]
+    fn antinodes() {
+        let m = VecMat::filled((10, 10), &b'.');
+        let p = |x, y| Point::new(x, y);
+        // antennas at (4, 3) and (5, 5) from 2024 day 8's example
+        let line: Vec<_> = m.line_through(p(4, 3), p(5, 5)).collect();
+        let expected = [(3, 1), (4, 3), (5, 5), (6, 7), (7, 9)];
+        assert_eq!(line, expected.map(|(x, y)| Point::new(x, y)));
+        // from outside the grid, and a line missing it
+        assert_eq!(m.line_through(p(-2, 0), p(-1, 0)).count(), 10);
+        assert_eq!(m.line_through(p(-2, -1), p(-1, -2)).count(), 0);
+    }
+
+    #[test]
+    fn guard() {
+        let m = VecMat::parse_bytes(["....#", ".....", "..^..", "....."]).unwrap();
+        let walked: Vec<_> = m
+            .ray_until(Point::new(4, 3), Dir::N, |_, c| *c == b'#')
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(
+            walked,
+            [Point::new(4, 3), Point::new(4, 2), Point::new(4, 1)]
+        );
+    }
+}
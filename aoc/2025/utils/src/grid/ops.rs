@@ -0,0 +1,235 @@
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Rem, Sub, SubAssign};
+
+use super::{Delta, Point};
+
+/// Integer types usable as coordinates, for the generic maths on [`Point`] and [`Delta`]
+pub trait Coord:
+    Copy
+    + Ord
+    + Default
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// |self - other| as Self, without overflowing on the subtraction for
+    /// unsigned types (unlike the integers' own `abs_diff`, which returns the
+    /// unsigned type, so it can still overflow for far apart signed values)
+    fn dist(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),*) => {
+        $(impl Coord for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+        })*
+    };
+}
+
+impl_coord!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+impl<T: Add<Output = T>> Add<Delta<T>> for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, rhs: Delta<T>) -> Self::Output {
+        Point::new(self.x + rhs.dx, self.y + rhs.dy)
+    }
+}
+
+impl<T: AddAssign> AddAssign<Delta<T>> for Point<T> {
+    fn add_assign(&mut self, rhs: Delta<T>) {
+        self.x += rhs.dx;
+        self.y += rhs.dy;
+    }
+}
+
+impl<T: Sub<Output = T>> Sub<Delta<T>> for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, rhs: Delta<T>) -> Self::Output {
+        Point::new(self.x - rhs.dx, self.y - rhs.dy)
+    }
+}
+
+impl<T: SubAssign> SubAssign<Delta<T>> for Point<T> {
+    fn sub_assign(&mut self, rhs: Delta<T>) {
+        self.x -= rhs.dx;
+        self.y -= rhs.dy;
+    }
+}
+
+/// The delta going from `rhs` to `self`;
+/// for unsigned types both coordinates of `self` must be the larger ones
+/// (else use eg [`Point::delta_to`])
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Delta<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Delta::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Delta<T> {
+    type Output = Delta<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Delta::new(self.dx + rhs.dx, self.dy + rhs.dy)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Delta<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.dx += rhs.dx;
+        self.dy += rhs.dy;
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Delta<T> {
+    type Output = Delta<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Delta::new(self.dx - rhs.dx, self.dy - rhs.dy)
+    }
+}
+
+impl<T: SubAssign> SubAssign for Delta<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.dx -= rhs.dx;
+        self.dy -= rhs.dy;
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Delta<T> {
+    type Output = Delta<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Delta::new(self.dx * rhs, self.dy * rhs)
+    }
+}
+
+impl<T: Copy + MulAssign> MulAssign<T> for Delta<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        self.dx *= rhs;
+        self.dy *= rhs;
+    }
+}
+
+impl<T> Point<T> {
+    /// Convert to a wider coordinate type
+    pub fn cast<U: From<T>>(self) -> Point<U> {
+        Point::new(self.x.into(), self.y.into())
+    }
+
+    /// Convert to another coordinate type, if both coordinates fit in it
+    pub fn try_cast<U: TryFrom<T>>(self) -> Option<Point<U>> {
+        Some(Point::new(self.x.try_into().ok()?, self.y.try_into().ok()?))
+    }
+}
+
+impl<T: Coord> Point<T> {
+    pub fn manhattan_dist(self, other: Self) -> T {
+        self.x.dist(other.x) + self.y.dist(other.y)
+    }
+
+    /// Distance where diagonal steps count as one, like a chess king's moves
+    pub fn chebyshev_dist(self, other: Self) -> T {
+        self.x.dist(other.x).max(self.y.dist(other.y))
+    }
+
+    /// Square of the euclidean distance
+    pub fn dist_sq(self, other: Self) -> T {
+        let (dx, dy) = (self.x.dist(other.x), self.y.dist(other.y));
+        dx * dx + dy * dy
+    }
+}
+
+impl<T> Delta<T> {
+    /// Convert to a wider coordinate type
+    pub fn cast<U: From<T>>(self) -> Delta<U> {
+        Delta::new(self.dx.into(), self.dy.into())
+    }
+
+    /// Convert to another coordinate type, if both components fit in it
+    pub fn try_cast<U: TryFrom<T>>(self) -> Option<Delta<U>> {
+        Some(Delta::new(
+            self.dx.try_into().ok()?,
+            self.dy.try_into().ok()?,
+        ))
+    }
+}
+
+impl<T: Coord> Delta<T> {
+    pub fn dot(self, other: Self) -> T {
+        self.dx * other.dx + self.dy * other.dy
+    }
+
+    /// The z component of the 3d cross product, ie the signed area of the
+    /// parallelogram spanned by the two deltas
+    pub fn cross(self, other: Self) -> T {
+        self.dx * other.dy - self.dy * other.dx
+    }
+
+    /// Length when diagonal steps count as one
+    pub fn chebyshev(self) -> T {
+        self.dx.dist(T::ZERO).max(self.dy.dist(T::ZERO))
+    }
+
+    /// Square of the euclidean length
+    pub fn norm_sq(self) -> T {
+        self.dot(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn antinodes() {
+        let (a, b) = (Point::new(4isize, 3), Point::new(5, 5));
+        let d = b - a;
+        assert_eq!(d, Delta::new(1, 2));
+        assert_eq!(b + d, Point::new(6, 7));
+        assert_eq!(a - d, Point::new(3, 1));
+        assert_eq!(a + d * 3, Point::new(7, 9));
+        let mut p = a;
+        p += d;
+        p -= d * 2;
+        assert_eq!(p, Point::new(3, 1));
+    }
+
+    #[test]
+    fn products_and_distances() {
+        let (u, v) = (Delta::new(3i64, 1), Delta::new(-1, 2));
+        assert_eq!(u.dot(v), -1);
+        assert_eq!(u.cross(v), 7);
+        assert_eq!(v.chebyshev(), 2);
+        assert_eq!(u.norm_sq(), 10);
+        let (p, q) = (Point::new(2usize, 9), Point::new(7, 5));
+        assert_eq!(p.manhattan_dist(q), 9);
+        assert_eq!(p.chebyshev_dist(q), 5);
+        assert_eq!(q.dist_sq(p), 41);
+    }
+
+    #[test]
+    fn casts() {
+        let p: Point<u8> = Point::new(3, 200);
+        assert_eq!(p.cast::<usize>(), Point::new(3usize, 200));
+        assert_eq!(p.try_cast::<i8>(), None);
+        assert_eq!(Point::new(-1i8, 2).try_cast::<u8>(), None);
+        assert_eq!(Delta::new(-1i8, 2).cast::<isize>(), Delta::new(-1isize, 2));
+    }
+}
@@ -0,0 +1,246 @@
+use std::{collections::HashMap, fmt};
+
+use super::{Point, VecMat};
+
+/// What a single input byte turns into when parsing a grid with markers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Parsed<T> {
+    /// A plain cell, its position isn't recorded
+    Cell(T),
+    /// A cell whose position is recorded, the byte may appear at most once
+    Unique(T),
+    /// A cell whose position is recorded, the byte may appear any number of times
+    Marker(T),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridParseError {
+    /// A row whose length doesn't match the first row's
+    /// (`row` is the index of the line in the input, blank lines included)
+    Ragged {
+        row: usize,
+        len: usize,
+        expected: usize,
+    },
+    /// The mapping closure didn't know what to make of this byte
+    UnknownByte { pos: Point<usize>, byte: u8 },
+    /// A byte meant to be unique appeared (at least) twice
+    DuplicateMarker {
+        byte: u8,
+        first: Point<usize>,
+        second: Point<usize>,
+    },
+}
+
+impl fmt::Display for GridParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridParseError::Ragged { row, len, expected } => {
+                write!(f, "row {row} has length {len}, expected {expected}")
+            }
+            GridParseError::UnknownByte { pos, byte } => write!(
+                f,
+                "unknown character {:?} at ({}, {})",
+                *byte as char, pos.x, pos.y
+            ),
+            GridParseError::DuplicateMarker {
+                byte,
+                first,
+                second,
+            } => write!(
+                f,
+                "unique marker {:?} found at both ({}, {}) and ({}, {})",
+                *byte as char, first.x, first.y, second.x, second.y
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GridParseError {}
+
+/// Positions of the marker bytes found while parsing a grid
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Markers(HashMap<u8, Vec<Point<usize>>>);
+
+impl Markers {
+    /// All the positions where the marker was found, in row-major order
+    pub fn get(&self, byte: u8) -> &[Point<usize>] {
+        self.0.get(&byte).map_or(&[], Vec::as_slice)
+    }
+
+    /// The position of a marker, if it was found
+    pub fn unique(&self, byte: u8) -> Option<Point<usize>> {
+        self.get(byte).first().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &[Point<usize>])> {
+        self.0.iter().map(|(&b, ps)| (b, ps.as_slice()))
+    }
+}
+
+impl<T> VecMat<T> {
+    /// Parse a grid from text lines, mapping each byte with `f`
+    /// (which returns None for bytes which aren't allowed).
+    /// Empty lines before and after the grid are skipped,
+    /// an empty line inside it is a [`GridParseError::Ragged`] row.
+    pub fn parse_with(
+        lines: impl IntoIterator<Item = impl AsRef<[u8]>>,
+        mut f: impl FnMut(u8) -> Option<T>,
+    ) -> Result<Self, GridParseError> {
+        Self::parse_with_markers(lines, |b| f(b).map(Parsed::Cell)).map(|(grid, _)| grid)
+    }
+
+    /// Parse a grid from text lines like [`VecMat::parse_with`],
+    /// additionally recording the positions of the bytes which `f`
+    /// flags as [`Parsed::Unique`] or [`Parsed::Marker`].
+    pub fn parse_with_markers(
+        lines: impl IntoIterator<Item = impl AsRef<[u8]>>,
+        mut f: impl FnMut(u8) -> Option<Parsed<T>>,
+    ) -> Result<(Self, Markers), GridParseError> {
+        let mut data = Vec::new();
+        let mut markers = Markers::default();
+        let mut cols = None;
+        // index of the first line of the grid, and of the first empty line after it
+        let mut start = None;
+        let mut blank = None;
+        for (i, line) in lines.into_iter().enumerate() {
+            let line = line.as_ref();
+            if line.is_empty() {
+                if start.is_some() {
+                    blank.get_or_insert(i);
+                }
+                continue;
+            }
+            let y = i - *start.get_or_insert(i);
+            let expected = *cols.get_or_insert(line.len());
+            // an empty line is only fine if nothing comes after it
+            if let Some(row) = blank {
+                return Err(GridParseError::Ragged {
+                    row,
+                    len: 0,
+                    expected,
+                });
+            }
+            if line.len() != expected {
+                return Err(GridParseError::Ragged {
+                    row: i,
+                    len: line.len(),
+                    expected,
+                });
+            }
+            for (x, &byte) in line.iter().enumerate() {
+                let pos = Point::new(x, y);
+                let cell = match f(byte) {
+                    Some(Parsed::Cell(c)) => c,
+                    Some(Parsed::Marker(c)) => {
+                        markers.0.entry(byte).or_default().push(pos);
+                        c
+                    }
+                    Some(Parsed::Unique(c)) => {
+                        let found = markers.0.entry(byte).or_default();
+                        if let Some(&first) = found.first() {
+                            return Err(GridParseError::DuplicateMarker {
+                                byte,
+                                first,
+                                second: pos,
+                            });
+                        }
+                        found.push(pos);
+                        c
+                    }
+                    None => return Err(GridParseError::UnknownByte { pos, byte }),
+                };
+                data.push(cell);
+            }
+        }
+        let cols = cols.unwrap_or(0);
+        let rows = data.len().checked_div(cols).unwrap_or(0);
+        Ok((VecMat { data, rows, cols }, markers))
+    }
+}
+
+impl VecMat<u8> {
+    /// Parse a grid of the raw bytes in the given lines
+    pub fn parse_bytes(
+        lines: impl IntoIterator<Item = impl AsRef<[u8]>>,
+    ) -> Result<Self, GridParseError> {
+        Self::parse_with(lines, Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "#####\n#S..#\n#.#E#\n#####\n";
+
+    fn maze_cell(b: u8) -> Option<Parsed<bool>> {
+        Some(match b {
+            b'.' => Parsed::Cell(true),
+            b'#' => Parsed::Cell(false),
+            b'S' | b'E' => Parsed::Unique(true),
+            _ => None?,
+        })
+    }
+
+    #[test]
+    fn markers() {
+        let (grid, markers) = VecMat::parse_with_markers(MAZE.lines(), maze_cell).unwrap();
+        assert_eq!(grid.shape(), (4, 5));
+        assert!(grid[Point::new(3, 2)]);
+        assert!(!grid[Point::new(2, 2)]);
+        assert_eq!(markers.unique(b'S'), Some(Point::new(1, 1)));
+        assert_eq!(markers.unique(b'E'), Some(Point::new(3, 2)));
+        assert_eq!(markers.get(b'#'), &[]);
+    }
+
+    #[test]
+    fn errors() {
+        let dup = VecMat::parse_with_markers("S.\n.S".lines(), maze_cell);
+        assert_eq!(
+            dup.unwrap_err(),
+            GridParseError::DuplicateMarker {
+                byte: b'S',
+                first: Point::new(0, 0),
+                second: Point::new(1, 1)
+            }
+        );
+        let ragged = VecMat::parse_bytes("abc\nab".lines());
+        assert_eq!(
+            ragged.unwrap_err(),
+            GridParseError::Ragged {
+                row: 1,
+                len: 2,
+                expected: 3
+            }
+        );
+        let ragged = VecMat::parse_bytes("\n\nabc\nab".lines());
+        assert_eq!(
+            ragged.unwrap_err(),
+            GridParseError::Ragged {
+                row: 3,
+                len: 2,
+                expected: 3
+            }
+        );
+        let split = VecMat::parse_bytes("abc\n\ndef".lines());
+        assert_eq!(
+            split.unwrap_err(),
+            GridParseError::Ragged {
+                row: 1,
+                len: 0,
+                expected: 3
+            }
+        );
+        let padded = VecMat::parse_bytes("\nabc\ndef\n\n".lines()).unwrap();
+        assert_eq!(padded.to_string(), "abc\ndef\n");
+        let unknown = VecMat::parse_with_markers("..\n.x".lines(), maze_cell);
+        assert_eq!(
+            unknown.unwrap_err(),
+            GridParseError::UnknownByte {
+                pos: Point::new(1, 1),
+                byte: b'x'
+            }
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! Exact geometry on simple polygons with integer vertices, given as the
+//! list of their vertices in order (either orientation, the last one is
+//! implicitly connected to the first).
+//!
+//! Everything works for unsigned coordinates too, signed intermediate
+//! values are handled as a sign and a magnitude.
+
+use std::cmp::Ordering;
+
+use super::{Coord, Point};
+use crate::maths::gcd;
+
+/// Where a point is with respect to a polygon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// A signed value as (is_negative, magnitude)
+#[derive(Debug, Clone, Copy)]
+struct Signed<T>(bool, T);
+
+impl<T: Coord> Signed<T> {
+    /// a - b
+    fn diff(a: T, b: T) -> Self {
+        Signed(a < b, a.dist(b))
+    }
+
+    fn mul(self, other: Self) -> Self {
+        let mag = self.1 * other.1;
+        Signed(self.0 != other.0 && mag != T::ZERO, mag)
+    }
+
+    fn cmp(self, other: Self) -> Ordering {
+        match (self.0, other.0) {
+            (false, false) => self.1.cmp(&other.1),
+            (true, true) => other.1.cmp(&self.1),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+/// Sign of the cross product (b - a) × (p - a), ie which side of the line
+/// through a and b the point p is on (Equal if on the line)
+fn orientation<T: Coord>(a: Point<T>, b: Point<T>, p: Point<T>) -> Ordering {
+    let l = Signed::diff(b.x, a.x).mul(Signed::diff(p.y, a.y));
+    let r = Signed::diff(b.y, a.y).mul(Signed::diff(p.x, a.x));
+    l.cmp(r)
+}
+
+fn edges<T: Copy>(vertices: &[Point<T>]) -> impl Iterator<Item = (Point<T>, Point<T>)> + '_ {
+    let next = vertices.iter().cycle().skip(1);
+    vertices.iter().copied().zip(next.copied())
+}
+
+/// Whether p is on the segment from a to b (ends included)
+fn on_segment<T: Coord>(a: Point<T>, b: Point<T>, p: Point<T>) -> bool {
+    orientation(a, b, p) == Ordering::Equal
+        && a.x.min(b.x) <= p.x
+        && p.x <= a.x.max(b.x)
+        && a.y.min(b.y) <= p.y
+        && p.y <= a.y.max(b.y)
+}
+
+/// Twice the area enclosed by the polygon (the shoelace formula),
+/// twice so that it's always an integer
+pub fn double_area<T: Coord>(vertices: &[Point<T>]) -> T {
+    let (mut pos, mut neg) = (T::ZERO, T::ZERO);
+    let origin = vertices.first().copied();
+    for (a, b) in edges(vertices) {
+        let o = origin.unwrap();
+        // use coordinates relative to the first vertex to keep the terms small
+        let l = Signed::diff(a.x, o.x).mul(Signed::diff(b.y, o.y));
+        let r = Signed::diff(b.x, o.x).mul(Signed::diff(a.y, o.y));
+        for (Signed(negative, mag), flip) in [(l, false), (r, true)] {
+            if negative != flip {
+                neg = neg + mag;
+            } else {
+                pos = pos + mag;
+            }
+        }
+    }
+    pos.dist(neg)
+}
+
+/// Number of lattice points on the polygon's boundary
+pub fn boundary_points<T: Coord>(vertices: &[Point<T>]) -> T {
+    edges(vertices).fold(T::ZERO, |acc, (a, b)| {
+        acc + gcd(a.x.dist(b.x), a.y.dist(b.y))
+    })
+}
+
+/// Number of lattice points strictly inside the polygon (by Pick's theorem)
+pub fn interior_points<T: Coord>(vertices: &[Point<T>]) -> T {
+    let two = T::ONE + T::ONE;
+    (double_area(vertices) + two - boundary_points(vertices)) / two
+}
+
+/// Number of lattice points inside or on the boundary of the polygon,
+/// eg the number of cells dug out by a closed trench and its interior
+pub fn lattice_points<T: Coord>(vertices: &[Point<T>]) -> T {
+    interior_points(vertices) + boundary_points(vertices)
+}
+
+/// Whether the point is inside, outside or on the boundary of the polygon
+pub fn locate<T: Coord>(vertices: &[Point<T>], p: Point<T>) -> Location {
+    let mut inside = false;
+    for (a, b) in edges(vertices) {
+        if on_segment(a, b, p) {
+            return Location::Boundary;
+        }
+        // does the edge cross the horizontal ray going right from p?
+        if (a.y > p.y) != (b.y > p.y) {
+            let upwards = b.y > a.y;
+            let side = orientation(a, b, p);
+            if (side == Ordering::Greater) == upwards {
+                inside = !inside;
+            }
+        }
+    }
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+/// Whether the point is inside the polygon or on its boundary
+pub fn contains<T: Coord>(vertices: &[Point<T>], p: Point<T>) -> bool {
+    locate(vertices, p) != Location::Outside
+}
+
+/// Whether the axis-aligned rectangle with opposite corners a and b lies
+/// entirely inside the polygon (boundary included), which must be rectilinear
+pub fn contains_rect<T: Coord>(vertices: &[Point<T>], a: Point<T>, b: Point<T>) -> bool {
+    debug_assert!(
+        edges(vertices).all(|(a, b)| a.x == b.x || a.y == b.y),
+        "polygon must be rectilinear"
+    );
+    let (min, max) = (
+        Point::new(a.x.min(b.x), a.y.min(b.y)),
+        Point::new(a.x.max(b.x), a.y.max(b.y)),
+    );
+    // the open intervals (lo, hi) and [elo, ehi] overlap
+    let overlaps = |lo: T, hi: T, ea: T, eb: T| ea.max(eb) > lo && ea.min(eb) < hi;
+    let strictly_between = |lo: T, v: T, hi: T| lo < v && v < hi;
+    // no edge may go through the interior of the rectangle
+    let crossed = edges(vertices).any(|(ea, eb)| {
+        if ea.x == eb.x {
+            strictly_between(min.x, ea.x, max.x) && overlaps(min.y, max.y, ea.y, eb.y)
+        } else {
+            strictly_between(min.y, ea.y, max.y) && overlaps(min.x, max.x, ea.x, eb.x)
+        }
+    });
+    if crossed {
+        return false;
+    }
+    // then the status only changes at the polygon's coordinates, so check
+    // the corners, every vertex coordinate along the sides and points in
+    // between (working with doubled coordinates so the midpoints are exact)
+    let double = |v: T| v + v;
+    let doubled: Vec<_> = vertices
+        .iter()
+        .map(|v| Point::new(double(v.x), double(v.y)))
+        .collect();
+    let stops = |lo: T, hi: T, coord: fn(&Point<T>) -> T| {
+        let mut stops: Vec<T> = vertices
+            .iter()
+            .map(coord)
+            .filter(|&v| lo < v && v < hi)
+            .chain([lo, hi])
+            .map(double)
+            .collect();
+        stops.sort_unstable();
+        stops.dedup();
+        let mids: Vec<_> = stops
+            .windows(2)
+            .map(|w| (w[0] + w[1]) / (T::ONE + T::ONE))
+            .collect();
+        stops.extend(mids);
+        stops
+    };
+    let xs = stops(min.x, max.x, |p| p.x);
+    let ys = stops(min.y, max.y, |p| p.y);
+    let sides = xs
+        .iter()
+        .flat_map(|&x| [Point::new(x, double(min.y)), Point::new(x, double(max.y))])
+        .chain(
+            ys.iter()
+                .flat_map(|&y| [Point::new(double(min.x), y), Point::new(double(max.x), y)]),
+        );
+    let center = Point::new(min.x + max.x, min.y + max.y);
+    sides.chain([center]).all(|p| contains(&doubled, p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the lagoon from 2023 day 18's example
+    fn lagoon() -> Vec<Point<isize>> {
+        [
+            (0, 0),
+            (6, 0),
+            (6, 5),
+            (4, 5),
+            (4, 7),
+            (6, 7),
+            (6, 9),
+            (1, 9),
+            (1, 7),
+            (0, 7),
+            (0, 5),
+            (2, 5),
+            (2, 2),
+            (0, 2),
+        ]
+        .map(|(x, y)| Point::new(x, y))
+        .to_vec()
+    }
+
+    #[test]
+    fn areas() {
+        let l = lagoon();
+        assert_eq!(boundary_points(&l), 38);
+        assert_eq!(lattice_points(&l), 62);
+        let mut reversed = l.clone();
+        reversed.reverse();
+        assert_eq!(double_area(&reversed), double_area(&l));
+        let triangle = [Point::new(0usize, 0), Point::new(4, 0), Point::new(0, 3)];
+        assert_eq!(double_area(&triangle), 12);
+        assert_eq!(boundary_points(&triangle), 8);
+        assert_eq!(interior_points(&triangle), 3);
+    }
+
+    #[test]
+    fn location() {
+        let l = lagoon();
+        assert_eq!(locate(&l, Point::new(3, 1)), Location::Inside);
+        assert_eq!(locate(&l, Point::new(6, 3)), Location::Boundary);
+        assert_eq!(locate(&l, Point::new(1, 3)), Location::Outside);
+        assert_eq!(locate(&l, Point::new(5, 6)), Location::Outside);
+        assert_eq!(locate(&l, Point::new(3, 8)), Location::Inside);
+        assert_eq!(locate(&l, Point::new(-1, 0)), Location::Outside);
+        let diamond = [
+            Point::new(2u8, 0),
+            Point::new(4, 2),
+            Point::new(2, 4),
+            Point::new(0, 2),
+        ];
+        assert_eq!(locate(&diamond, Point::new(2, 2)), Location::Inside);
+        assert_eq!(locate(&diamond, Point::new(3, 1)), Location::Boundary);
+        assert_eq!(locate(&diamond, Point::new(0, 0)), Location::Outside);
+    }
+
+    #[test]
+    fn rectangles() {
+        // the red tiles from 2025 day 9's example
+        let tiles = [
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ]
+        .map(|(x, y)| Point::new(x, y));
+        assert!(contains_rect(
+            &tiles,
+            Point::new(9usize, 5),
+            Point::new(2, 3)
+        ));
+        assert!(contains_rect(&tiles, Point::new(7, 3), Point::new(11, 1)));
+        assert!(!contains_rect(&tiles, Point::new(2, 5), Point::new(11, 1)));
+        assert!(!contains_rect(&tiles, Point::new(9, 7), Point::new(2, 3)));
+        // segments leaving the polygon at a vertex
+        assert!(!contains_rect(&tiles, Point::new(7, 3), Point::new(7, 0)));
+        assert!(!contains_rect(&tiles, Point::new(2, 3), Point::new(2, 6)));
+        assert!(contains_rect(&tiles, Point::new(2, 3), Point::new(11, 3)));
+        assert!(contains_rect(&tiles, Point::new(9, 7), Point::new(9, 1)));
+        // the notch of a U shape is outside even if its corners are on the boundary
+        let u = [
+            (0, 0),
+            (1, 0),
+            (1, 2),
+            (2, 2),
+            (2, 0),
+            (3, 0),
+            (3, 3),
+            (0, 3),
+        ]
+        .map(|(x, y)| Point::new(x, y));
+        assert!(!contains_rect(&u, Point::new(1isize, 0), Point::new(2, 2)));
+        assert!(contains_rect(&u, Point::new(0isize, 2), Point::new(3, 3)));
+    }
+}
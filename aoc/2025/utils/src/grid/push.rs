@@ -0,0 +1,190 @@
+use core::fmt;
+use std::collections::{HashMap, VecDeque};
+
+use super::{Dir, Point, VecMat};
+
+/// What's in a cell of an [`Objects`] grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Occupant {
+    Empty,
+    /// Never moves and stops whatever is pushed into it
+    Wall,
+    /// Part of the object with this id, objects can cover several cells
+    Object(usize),
+}
+
+/// Why an object couldn't be pushed, see [`Objects::push`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PushError {
+    /// There's no object with this id
+    NoSuchObject(usize),
+    /// Something is in the way at this cell (a wall, or beyond the edge of the grid)
+    Blocked(Point<usize>),
+}
+
+impl fmt::Display for PushError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PushError::NoSuchObject(id) => write!(f, "no object with id {id}"),
+            PushError::Blocked(p) => write!(f, "blocked at ({}, {})", p.x, p.y),
+        }
+    }
+}
+
+impl std::error::Error for PushError {}
+
+/// Objects covering one or more cells of a grid, which can push each other
+/// around: pushing an object moves every object in its way, transitively
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Objects {
+    occupants: VecMat<Occupant>,
+    /// The cells covered by each object
+    cells: HashMap<usize, Vec<Point<usize>>>,
+}
+
+impl Objects {
+    pub fn new(occupants: VecMat<Occupant>) -> Self {
+        let mut cells: HashMap<_, Vec<_>> = HashMap::new();
+        for (p, o) in occupants.iter_pos() {
+            if let Occupant::Object(id) = *o {
+                cells.entry(id).or_default().push(p);
+            }
+        }
+        Self { occupants, cells }
+    }
+
+    /// Build from a grid, `classify` telling what each cell holds
+    /// (eg the two halves of a wide box share an id)
+    pub fn from_grid<T>(grid: &VecMat<T>, classify: impl Fn(Point<usize>, &T) -> Occupant) -> Self {
+        Self::new(VecMat::filled_with(grid.shape(), |p| classify(p, &grid[p])))
+    }
+
+    pub fn occupants(&self) -> &VecMat<Occupant> {
+        &self.occupants
+    }
+
+    pub fn get(&self, p: Point<usize>) -> Option<Occupant> {
+        self.occupants.get(p).copied()
+    }
+
+    /// The cells covered by the object, None if there's no such object
+    pub fn cells_of(&self, id: usize) -> Option<&[Point<usize>]> {
+        self.cells.get(&id).map(|c| c.as_slice())
+    }
+
+    /// The objects' ids and the cells they cover
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &[Point<usize>])> {
+        self.cells.iter().map(|(&id, c)| (id, c.as_slice()))
+    }
+
+    /// The objects that would move if `id` was pushed in direction `dir`
+    /// (starting with `id`, in the order they're reached), or the first cell
+    /// found blocking the push
+    pub fn dry_run(&self, id: usize, dir: Dir) -> Result<Vec<usize>, PushError> {
+        if !self.cells.contains_key(&id) {
+            return Err(PushError::NoSuchObject(id));
+        }
+        let mut affected = vec![id];
+        let mut queue = VecDeque::from([id]);
+        while let Some(obj) = queue.pop_front() {
+            for &c in self.cells_of(obj).unwrap_or_default() {
+                let n = c.neighbour(dir);
+                match self.get(n) {
+                    None | Some(Occupant::Wall) => return Err(PushError::Blocked(n)),
+                    Some(Occupant::Object(other)) if !affected.contains(&other) => {
+                        affected.push(other);
+                        queue.push_back(other);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(affected)
+    }
+
+    /// Push object `id` one step in direction `dir`, moving all the objects
+    /// in the way at once, or none of them if something blocks the push;
+    /// returns what [`Objects::dry_run`] would
+    pub fn push(&mut self, id: usize, dir: Dir) -> Result<Vec<usize>, PushError> {
+        let affected = self.dry_run(id, dir)?;
+        // clear everything first so objects don't overwrite each other
+        for obj in &affected {
+            for &c in self.cells.get(obj).into_iter().flatten() {
+                self.occupants[c] = Occupant::Empty;
+            }
+        }
+        for &obj in &affected {
+            let Some(cells) = self.cells.get_mut(&obj) else {
+                continue;
+            };
+            for c in cells {
+                *c = c.neighbour(dir);
+                self.occupants[*c] = Occupant::Object(obj);
+            }
+        }
+        Ok(affected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOT: usize = usize::MAX;
+
+    /// a warehouse from 2024 day 15's example, widened
+    fn warehouse() -> Objects {
+        let narrow = [
+            "#######", "#...#.#", "#.....#", "#..OO@#", "#..O..#", "#.....#", "#######",
+        ];
+        let wide = narrow.map(|row| {
+            row.chars()
+                .map(|c| match c {
+                    '#' => "##",
+                    'O' => "[]",
+                    '@' => "@.",
+                    _ => "..",
+                })
+                .collect::<String>()
+        });
+        let grid = VecMat::parse_bytes(wide.iter()).unwrap();
+        Objects::from_grid(&grid, |p, c| match c {
+            b'#' => Occupant::Wall,
+            b'@' => Occupant::Object(ROBOT),
+            // boxes are identified by their left half
+            b'[' => Occupant::Object(100 * p.y + p.x),
+            b']' => Occupant::Object(100 * p.y + p.x - 1),
+            _ => Occupant::Empty,
+        })
+    }
+
+    #[test]
+    fn wide_boxes() {
+        let mut w = warehouse();
+        let before = w.clone();
+        assert_eq!(w.dry_run(ROBOT, Dir::W).map(|a| a.len()), Ok(3));
+        assert_eq!(w, before);
+        for dir in [Dir::W, Dir::S, Dir::S, Dir::W, Dir::W] {
+            assert!(w.push(ROBOT, dir).is_ok());
+        }
+        // three boxes fanning out, then a wall above the rightmost one
+        assert_eq!(w.push(ROBOT, Dir::N).map(|a| a.len()), Ok(4));
+        let before = w.clone();
+        assert_eq!(
+            w.push(ROBOT, Dir::N),
+            Err(PushError::Blocked(Point::new(8, 1)))
+        );
+        assert_eq!(w.push(42, Dir::N), Err(PushError::NoSuchObject(42)));
+        assert_eq!(w, before);
+        assert_eq!(w.cells_of(ROBOT), Some(&[Point::new(7, 4)][..]));
+        for dir in [Dir::W, Dir::W, Dir::N, Dir::N] {
+            let _ = w.push(ROBOT, dir);
+        }
+        let gps: usize = w
+            .iter()
+            .filter(|&(id, _)| id != ROBOT)
+            .map(|(_, cells)| cells.iter().map(|p| 100 * p.y + p.x).min().unwrap())
+            .sum();
+        assert_eq!(gps, 105 + 207 + 306);
+    }
+}
@@ -0,0 +1,254 @@
+use std::ops::Range;
+
+use super::{Coord, Delta, Dir, Point, VecMat};
+
+/// An axis-aligned rectangle of lattice points, from `min` included
+/// to `max` excluded (so it's empty if they share a coordinate)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect<T> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+/// The bounds of a grid of the given (width, height), as taken by
+/// eg [`Point::in_bounds`]
+impl<T: Coord> From<(T, T)> for Rect<T> {
+    fn from((width, height): (T, T)) -> Self {
+        Rect::new(Point::new(T::ZERO, T::ZERO), Point::new(width, height))
+    }
+}
+
+impl<T: Coord> Rect<T> {
+    /// The rectangle from min (included) to max (excluded)
+    pub fn new(min: Point<T>, max: Point<T>) -> Self {
+        let max = Point::new(max.x.max(min.x), max.y.max(min.y));
+        Self { min, max }
+    }
+
+    /// The rectangle with the two given opposite corners, both included
+    pub fn from_corners(a: Point<T>, b: Point<T>) -> Self {
+        Self {
+            min: Point::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point::new(a.x.max(b.x) + T::ONE, a.y.max(b.y) + T::ONE),
+        }
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y
+    }
+
+    /// Number of points in the rectangle
+    pub fn area(&self) -> T {
+        self.width() * self.height()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.area() == T::ZERO
+    }
+
+    pub fn xs(&self) -> Range<T> {
+        self.min.x..self.max.x
+    }
+
+    pub fn ys(&self) -> Range<T> {
+        self.min.y..self.max.y
+    }
+
+    pub fn contains(&self, p: Point<T>) -> bool {
+        self.xs().contains(&p.x) && self.ys().contains(&p.y)
+    }
+
+    pub fn contains_rect(&self, other: &Self) -> bool {
+        other.is_empty() || self.intersection(other) == Some(*other)
+    }
+
+    /// The points in both rectangles, None if there's none
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Point::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y));
+        let max = Point::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y));
+        (min.x < max.x && min.y < max.y).then_some(Self { min, max })
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The smallest rectangle containing both
+    pub fn hull(&self, other: &Self) -> Self {
+        Self {
+            min: Point::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Point::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    /// The points in the rectangle, in row-major order
+    pub fn iter_points(&self) -> impl Iterator<Item = Point<T>> + use<T>
+    where
+        Range<T>: Iterator<Item = T>,
+    {
+        let xs = self.xs();
+        self.ys()
+            .flat_map(move |y| xs.clone().map(move |x| Point::new(x, y)))
+    }
+
+    /// Number of points covered by at least one of the rectangles,
+    /// computed with a sweep line over the x coordinates
+    pub fn union_area(rects: &[Self]) -> T {
+        let mut xs: Vec<T> = rects.iter().flat_map(|r| [r.min.x, r.max.x]).collect();
+        xs.sort_unstable();
+        xs.dedup();
+        let mut total = T::ZERO;
+        for slab in xs.windows(2) {
+            let (lo, hi) = (slab[0], slab[1]);
+            // the rectangles spanning this vertical slab, as y intervals
+            let mut spans: Vec<_> = rects
+                .iter()
+                .filter(|r| r.min.x <= lo && hi <= r.max.x && !r.is_empty())
+                .map(|r| (r.min.y, r.max.y))
+                .collect();
+            spans.sort_unstable();
+            let mut covered = T::ZERO;
+            let mut reached: Option<T> = None;
+            for (start, end) in spans {
+                let start = reached.map_or(start, |r| start.max(r));
+                if end > start {
+                    covered = covered + (end - start);
+                }
+                reached = Some(reached.map_or(end, |r| r.max(end)));
+            }
+            total = total + covered * (hi - lo);
+        }
+        total
+    }
+}
+
+impl Rect<isize> {
+    /// The range of step counts k for which `from + k * dir` is in the rectangle
+    fn steps_inside(&self, from: Point<isize>, dir: Dir) -> Range<isize> {
+        let d = dir.to_delta::<isize>();
+        let mut steps = isize::MIN..isize::MAX;
+        for (start, step, lo, hi) in [
+            (from.x, d.dx, self.min.x, self.max.x),
+            (from.y, d.dy, self.min.y, self.max.y),
+        ] {
+            let (first, end) = match step {
+                0 if (lo..hi).contains(&start) => continue,
+                0 => return 0..0,
+                1 => (lo - start, hi - start),
+                _ => (start - hi + 1, start - lo + 1),
+            };
+            steps = steps.start.max(first)..steps.end.min(end);
+        }
+        steps
+    }
+
+    /// The first and last points of the ray starting at `from` going in `dir`
+    /// which are in the rectangle, if any
+    pub fn clip_ray(&self, from: Point<isize>, dir: Dir) -> Option<(Point<isize>, Point<isize>)> {
+        self.clip_steps(from, dir, 0..isize::MAX)
+    }
+
+    /// The first and last points of the segment from `a` to `b` (both included)
+    /// which are in the rectangle, if any; the segment must be horizontal,
+    /// vertical or diagonal
+    pub fn clip_segment(
+        &self,
+        a: Point<isize>,
+        b: Point<isize>,
+    ) -> Option<(Point<isize>, Point<isize>)> {
+        if a == b {
+            return self.contains(a).then_some((a, a));
+        }
+        let d = b - a;
+        assert!(
+            d.dx == 0 || d.dy == 0 || d.dx.abs() == d.dy.abs(),
+            "segment must be horizontal, vertical or diagonal"
+        );
+        let dir = Dir::ALL
+            .into_iter()
+            .find(|dir| dir.to_delta::<isize>() == Delta::new(d.dx.signum(), d.dy.signum()))
+            .unwrap();
+        self.clip_steps(a, dir, 0..d.dx.abs().max(d.dy.abs()) + 1)
+    }
+
+    fn clip_steps(
+        &self,
+        from: Point<isize>,
+        dir: Dir,
+        allowed: Range<isize>,
+    ) -> Option<(Point<isize>, Point<isize>)> {
+        let inside = self.steps_inside(from, dir);
+        let (first, end) = (inside.start.max(allowed.start), inside.end.min(allowed.end));
+        let at = |k: isize| from + dir.to_delta::<isize>() * k;
+        (first < end).then(|| (at(first), at(end - 1)))
+    }
+}
+
+impl<T> VecMat<T> {
+    /// The rectangle of the grid's valid points
+    pub fn bounds(&self) -> Rect<usize> {
+        Rect::from((self.cols(), self.rows()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basics() {
+        let r = Rect::from_corners(Point::new(11usize, 1), Point::new(2, 5));
+        assert_eq!((r.width(), r.height(), r.area()), (10, 5, 50));
+        assert!(r.contains(Point::new(11, 5)) && !r.contains(Point::new(12, 5)));
+        let s = Rect::from_corners(Point::new(9, 7), Point::new(7, 3));
+        assert_eq!(
+            r.intersection(&s),
+            Some(Rect::from_corners(Point::new(7, 3), Point::new(9, 5)))
+        );
+        assert!(r.hull(&s).contains_rect(&s));
+        assert_eq!(r.intersection(&Rect::from((2, 2))), None);
+        assert_eq!(Rect::from((3, 2)).iter_points().count(), 6);
+        assert!(Point::new(2, 1).in_bounds(Rect::from((3, 2))));
+        assert!(!Point::new(2, 1).in_bounds((2, 3)));
+    }
+
+    #[test]
+    fn union() {
+        let rects = [
+            Rect::from_corners(Point::new(0isize, 0), Point::new(3, 3)),
+            Rect::from_corners(Point::new(2, 2), Point::new(5, 5)),
+            Rect::from_corners(Point::new(10, 0), Point::new(10, 0)),
+            Rect::from_corners(Point::new(1, 1), Point::new(2, 2)),
+        ];
+        assert_eq!(Rect::union_area(&rects), 16 + 16 - 4 + 1);
+        assert_eq!(Rect::<isize>::union_area(&[]), 0);
+    }
+
+    #[test]
+    fn clipping() {
+        let r = Rect::from_corners(Point::new(0isize, 0), Point::new(4, 3));
+        let from = Point::new(-2, 5);
+        assert_eq!(
+            r.clip_ray(from, Dir::NE),
+            Some((Point::new(0, 3), Point::new(3, 0)))
+        );
+        assert_eq!(r.clip_ray(from, Dir::N), None);
+        assert_eq!(
+            r.clip_ray(Point::new(2, 2), Dir::W),
+            Some((Point::new(2, 2), Point::new(0, 2)))
+        );
+        assert_eq!(
+            r.clip_segment(Point::new(6, 1), Point::new(3, 1)),
+            Some((Point::new(4, 1), Point::new(3, 1)))
+        );
+        assert_eq!(r.clip_segment(Point::new(6, 1), Point::new(5, 1)), None);
+        assert_eq!(
+            r.clip_segment(Point::new(1, 1), Point::new(1, 1)),
+            Some((Point::new(1, 1), Point::new(1, 1)))
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use super::{Dir, Point, VecMat};
+
+/// One side of a unit edge of a cell, on the boundary of a region:
+/// the cell is inside the region, its neighbour in `dir` is not
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub cell: Point<usize>,
+    pub dir: Dir,
+}
+
+/// A connected region of a grid, see [`VecMat::regions`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// The label of the region's cells in [`Regions::labels`]
+    pub label: usize,
+    /// Any cell of the region (the first one in row-major order)
+    pub start: Point<usize>,
+    /// Number of cells in the region
+    pub area: usize,
+    /// Number of distinct straight sides of the region's boundary
+    pub sides: usize,
+    /// Top left corner of the bounding box (inclusive)
+    pub min: Point<usize>,
+    /// Bottom right corner of the bounding box (inclusive)
+    pub max: Point<usize>,
+    /// All the unit edges on the region's boundary (inner ones too)
+    pub edges: Vec<Edge>,
+}
+
+impl Region {
+    /// Number of unit edges on the region's boundary
+    pub fn perimeter(&self) -> usize {
+        self.edges.len()
+    }
+}
+
+/// Connected component labelling of a grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regions {
+    /// The label of the region each cell belongs to, indexes into `regions`
+    pub labels: VecMat<usize>,
+    pub regions: Vec<Region>,
+}
+
+impl Regions {
+    /// The region a point belongs to
+    pub fn region_of(&self, p: Point<usize>) -> Option<&Region> {
+        self.labels.get(p).map(|&l| &self.regions[l])
+    }
+}
+
+/// Count the straight runs made by the given edges,
+/// ie how many sides an edge set traces out
+fn count_sides(edges: &[Edge]) -> usize {
+    // for each direction and line, the positions along the line
+    let mut lines: HashMap<(Dir, usize), Vec<usize>> = HashMap::new();
+    for e in edges {
+        let (line, pos) = if e.dir.is_vertical() {
+            (e.cell.y, e.cell.x)
+        } else {
+            (e.cell.x, e.cell.y)
+        };
+        lines.entry((e.dir, line)).or_default().push(pos);
+    }
+    lines
+        .into_values()
+        .map(|mut positions| {
+            positions.sort_unstable();
+            1 + positions.windows(2).filter(|w| w[1] - w[0] > 1).count()
+        })
+        .sum()
+}
+
+impl<T> VecMat<T> {
+    /// Split the grid into 4-connected regions of equal cells
+    pub fn regions(&self) -> Regions
+    where
+        T: PartialEq,
+    {
+        self.regions_by(|a, b| a == b)
+    }
+
+    /// Split the grid into 4-connected regions, where two neighbouring cells
+    /// are in the same region if `same` holds for them
+    pub fn regions_by(&self, same: impl Fn(&T, &T) -> bool) -> Regions {
+        let mut labels: VecMat<Option<usize>> = VecMat::filled(self.shape(), &None);
+        let mut regions = Vec::new();
+        let mut stack = Vec::new();
+        for (start, _) in self.iter_pos() {
+            if labels[start].is_some() {
+                continue;
+            }
+            let label = regions.len();
+            let mut region = Region {
+                label,
+                start,
+                area: 0,
+                sides: 0,
+                min: start,
+                max: start,
+                edges: vec![],
+            };
+            labels[start] = Some(label);
+            stack.push(start);
+            while let Some(p) = stack.pop() {
+                region.area += 1;
+                region.min = Point::new(region.min.x.min(p.x), region.min.y.min(p.y));
+                region.max = Point::new(region.max.x.max(p.x), region.max.y.max(p.y));
+                for dir in Dir::CROSS {
+                    let n = p.neighbour(dir);
+                    match self.get(n) {
+                        Some(cell) if same(&self[p], cell) => {
+                            if labels[n].is_none() {
+                                labels[n] = Some(label);
+                                stack.push(n);
+                            }
+                        }
+                        _ => region.edges.push(Edge { cell: p, dir }),
+                    }
+                }
+            }
+            region.sides = count_sides(&region.edges);
+            regions.push(region);
+        }
+        let labels = VecMat::filled_with(self.shape(), |p| {
+            labels[p].expect("every cell should have been labelled")
+        });
+        Regions { labels, regions }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn garden() {
+        let garden = VecMat::parse_bytes(["AAAA", "BBCD", "BBCC", "EEEC"]).unwrap();
+        let regions = garden.regions();
+        assert_eq!(regions.regions.len(), 5);
+        let stats = |p| {
+            let r = regions.region_of(p).unwrap();
+            (r.area, r.perimeter(), r.sides)
+        };
+        assert_eq!(stats(Point::new(0, 0)), (4, 10, 4));
+        assert_eq!(stats(Point::new(0, 1)), (4, 8, 4));
+        assert_eq!(stats(Point::new(2, 1)), (4, 10, 8));
+        assert_eq!(stats(Point::new(3, 1)), (1, 4, 4));
+        let c = regions.region_of(Point::new(3, 3)).unwrap();
+        assert_eq!((c.min, c.max), (Point::new(2, 1), Point::new(3, 3)));
+    }
+
+    #[test]
+    fn holes() {
+        let garden = VecMat::parse_bytes(["EEEEE", "EXXXX", "EEEEE", "EXXXX", "EEEEE"]).unwrap();
+        let e = &garden.regions().regions[0];
+        assert_eq!((e.area, e.sides), (17, 12));
+        let garden =
+            VecMat::parse_bytes(["AAAAAA", "AAABBA", "AAABBA", "ABBAAA", "ABBAAA", "AAAAAA"]);
+        let a = &garden.unwrap().regions().regions[0];
+        assert_eq!((a.area, a.sides), (28, 12));
+    }
+}
@@ -0,0 +1,304 @@
+use core::fmt::{self, Display, Write};
+use std::collections::HashSet;
+
+use super::{MatDisplayWith, Point};
+
+/// A terminal colour, either one of the 8 basic ones or 24-bit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Colour {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Rgb(u8, u8, u8),
+}
+
+impl Colour {
+    /// The SGR parameters selecting this colour, as foreground or background
+    fn sgr(self, background: bool) -> String {
+        let base = if background { 40 } else { 30 };
+        let basic = |n: u8| (base + n).to_string();
+        match self {
+            Colour::Black => basic(0),
+            Colour::Red => basic(1),
+            Colour::Green => basic(2),
+            Colour::Yellow => basic(3),
+            Colour::Blue => basic(4),
+            Colour::Magenta => basic(5),
+            Colour::Cyan => basic(6),
+            Colour::White => basic(7),
+            Colour::Rgb(r, g, b) => format!("{};2;{r};{g};{b}", base + 8),
+        }
+    }
+}
+
+/// How to paint the cells of a [`Layer`]; unset attributes are left
+/// to lower priority layers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    pub fg: Option<Colour>,
+    pub bg: Option<Colour>,
+    pub bold: bool,
+    /// Replacement for the cell's own rendering
+    pub glyph: Option<char>,
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn fg(self, colour: Colour) -> Self {
+        Self {
+            fg: Some(colour),
+            ..self
+        }
+    }
+
+    pub fn bg(self, colour: Colour) -> Self {
+        Self {
+            bg: Some(colour),
+            ..self
+        }
+    }
+
+    pub fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    pub fn glyph(self, glyph: char) -> Self {
+        Self {
+            glyph: Some(glyph),
+            ..self
+        }
+    }
+
+    /// Fill in the attributes this style doesn't set from `other`
+    fn or(self, other: Self) -> Self {
+        Self {
+            fg: self.fg.or(other.fg),
+            bg: self.bg.or(other.bg),
+            bold: self.bold || other.bold,
+            glyph: self.glyph.or(other.glyph),
+        }
+    }
+
+    /// The escape sequence switching to this style, empty if it has no colours
+    pub(super) fn escape(&self) -> String {
+        let codes: Vec<_> = (self.bold.then(|| "1".to_string()).into_iter())
+            .chain(self.fg.map(|c| c.sgr(false)))
+            .chain(self.bg.map(|c| c.sgr(true)))
+            .collect();
+        if codes.is_empty() {
+            String::new()
+        } else {
+            format!("\x1b[{}m", codes.join(";"))
+        }
+    }
+}
+
+/// A named set of cells painted with a style, see [`Layered`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layer {
+    pub name: String,
+    pub style: Style,
+    /// Layers with higher priority are painted over lower ones
+    pub priority: i32,
+    pub cells: HashSet<Point<usize>>,
+}
+
+/// A grid rendering with highlight layers, see [`MatDisplayWith::layered`]
+pub struct Layered<'a, T, F> {
+    base: MatDisplayWith<'a, T, F>,
+    /// Sorted by decreasing priority, in insertion order among equal ones
+    layers: Vec<Layer>,
+    rulers: bool,
+    plain: bool,
+}
+
+impl<'a, T, F> MatDisplayWith<'a, T, F> {
+    /// Render with highlight layers on top
+    pub fn layered(self) -> Layered<'a, T, F> {
+        Layered {
+            base: self,
+            layers: Vec::new(),
+            rulers: false,
+            plain: false,
+        }
+    }
+}
+
+impl<T, F> Layered<'_, T, F> {
+    /// Add a layer painting `cells` with `style`; among layers with the
+    /// same priority, the first added wins
+    pub fn layer(
+        mut self,
+        name: impl Into<String>,
+        style: Style,
+        priority: i32,
+        cells: impl IntoIterator<Item = Point<usize>>,
+    ) -> Self {
+        let at = self.layers.partition_point(|l| l.priority >= priority);
+        let layer = Layer {
+            name: name.into(),
+            style,
+            priority,
+            cells: cells.into_iter().collect(),
+        };
+        self.layers.insert(at, layer);
+        self
+    }
+
+    pub fn get_layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|l| l.name == name)
+    }
+
+    /// The layer with the given name, eg to add more cells to it
+    pub fn get_layer_mut(&mut self, name: &str) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|l| l.name == name)
+    }
+
+    pub fn remove_layer(&mut self, name: &str) -> Option<Layer> {
+        let i = self.layers.iter().position(|l| l.name == name)?;
+        Some(self.layers.remove(i))
+    }
+
+    /// Show the coordinates along the top and left edges
+    pub fn with_rulers(self) -> Self {
+        Self {
+            rulers: true,
+            ..self
+        }
+    }
+
+    /// Don't emit any escape sequences (glyphs are still replaced),
+    /// eg for writing to a file
+    pub fn plain(self) -> Self {
+        Self {
+            plain: true,
+            ..self
+        }
+    }
+
+    /// The combined style of the layers containing `p`
+    fn style_at(&self, p: Point<usize>) -> Style {
+        self.layers
+            .iter()
+            .filter(|l| l.cells.contains(&p))
+            .fold(Style::default(), |acc, l| acc.or(l.style))
+    }
+}
+
+impl<T, F, U> Display for Layered<'_, T, F>
+where
+    F: Fn(usize, usize, &T) -> U,
+    U: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (rows, cols) = self.base.grid.shape();
+        let margin = rows.saturating_sub(1).to_string().len();
+        if self.rulers {
+            // one line per digit of the column numbers, most significant first
+            let digits = cols.saturating_sub(1).to_string().len();
+            for place in (0..digits as u32).rev() {
+                write!(f, "{:margin$} ", "")?;
+                for x in 0..cols {
+                    let shown = x == 0 && place == 0 || x >= 10usize.pow(place);
+                    if shown {
+                        write!(f, "{}", x / 10usize.pow(place) % 10)?;
+                    } else {
+                        f.write_char(' ')?;
+                    }
+                }
+                writeln!(f)?;
+            }
+        }
+        let mut cell = String::new();
+        for y in 0..rows {
+            if self.rulers {
+                write!(f, "{y:>margin$} ")?;
+            }
+            for x in 0..cols {
+                let p = Point::new(x, y);
+                let style = self.style_at(p);
+                cell.clear();
+                match (style.glyph, self.base.grid.cell(p)) {
+                    (Some(g), _) => cell.push(g),
+                    (None, Some(c)) => write!(cell, "{}", (self.base.f)(x, y, c))?,
+                    (None, None) => cell.push(self.base.blank),
+                }
+                let escape = if self.plain {
+                    String::new()
+                } else {
+                    style.escape()
+                };
+                if escape.is_empty() {
+                    f.write_str(&cell)?;
+                } else {
+                    write!(f, "{escape}{cell}\x1b[0m")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::VecMat;
+
+    #[test]
+    fn layers() {
+        let m = VecMat::parse_bytes(["abc", "def"]).unwrap();
+        let fmt = m.formatter_with(|_, _, &c| c as char);
+        let layered = fmt
+            .layered()
+            .layer(
+                "path",
+                Style::new().fg(Colour::Red),
+                0,
+                [Point::new(0, 0), Point::new(1, 0)],
+            )
+            .layer(
+                "walls",
+                Style::new().glyph('#'),
+                1,
+                [Point::new(1, 0), Point::new(2, 1)],
+            )
+            .layer(
+                "start",
+                Style::new().bg(Colour::Rgb(1, 2, 3)).bold(),
+                0,
+                [Point::new(0, 0)],
+            );
+        assert_eq!(
+            layered.to_string(),
+            "\x1b[1;31;48;2;1;2;3ma\x1b[0m\x1b[31m#\x1b[0mc\nde#\n"
+        );
+        assert_eq!(layered.plain().to_string(), "a#c\nde#\n");
+    }
+
+    #[test]
+    fn rulers() {
+        let m = VecMat::filled((11, 12), &b'.');
+        let mut layered = m.formatter_with(|_, _, &c| c as char).layered().plain();
+        layered = layered.layer("x", Style::new().glyph('x'), 0, []);
+        layered
+            .get_layer_mut("x")
+            .unwrap()
+            .cells
+            .insert(Point::new(11, 10));
+        let rendered = layered.with_rulers().to_string();
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(lines[0], "             11");
+        assert_eq!(lines[1], "   012345678901");
+        assert_eq!(lines[2], " 0 ............");
+        assert_eq!(lines[12], "10 ...........x");
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::{HashSet, VecDeque};
+
+use super::{Dir, Point, VecMat};
+
+/// Result of a flood fill over a grid, see [`VecMat::flood_fill`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FloodFill {
+    /// All the points that were reached, sources included
+    pub reached: HashSet<Point<usize>>,
+    /// For each reached point, the point it was first reached from
+    /// (None for sources and unreached points)
+    pub predecessors: VecMat<Option<Point<usize>>>,
+}
+
+impl FloodFill {
+    /// A shortest path from one of the sources to `p` (both ends included),
+    /// or None if `p` wasn't reached
+    pub fn path_to(&self, p: Point<usize>) -> Option<Vec<Point<usize>>> {
+        if !self.reached.contains(&p) {
+            return None;
+        }
+        let mut path = vec![p];
+        let mut cur = p;
+        while let Some(prev) = self.predecessors[cur] {
+            path.push(prev);
+            cur = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl<T> VecMat<T> {
+    /// Breadth-first search from all of `sources` at once, moving in the given
+    /// directions (eg [`Dir::CROSS`] or [`Dir::ALL`]) onto cells for which
+    /// `passable` holds; calls `visit(point, from, distance)` for every
+    /// reached point in order of distance (`from` is None for sources).
+    fn bfs(
+        &self,
+        sources: impl IntoIterator<Item = Point<usize>>,
+        dirs: &[Dir],
+        passable: impl Fn(Point<usize>, &T) -> bool,
+        mut visit: impl FnMut(Point<usize>, Option<Point<usize>>, usize),
+    ) {
+        let mut seen = VecMat::filled(self.shape(), &false);
+        let mut queue = VecDeque::new();
+        for s in sources {
+            if let Some(false) = seen.get(s) {
+                seen[s] = true;
+                visit(s, None, 0);
+                queue.push_back((s, 0));
+            }
+        }
+        while let Some((p, dist)) = queue.pop_front() {
+            for &dir in dirs {
+                let n = p.neighbour(dir);
+                match (self.get(n), seen.get(n)) {
+                    (Some(cell), Some(false)) if passable(n, cell) => {
+                        seen[n] = true;
+                        visit(n, Some(p), dist + 1);
+                        queue.push_back((n, dist + 1));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Distance of every cell from the nearest of `sources`, moving in the
+    /// given directions onto cells for which `passable` holds.
+    /// Sources are always at distance 0, unreachable cells are None.
+    pub fn bfs_distances(
+        &self,
+        sources: impl IntoIterator<Item = Point<usize>>,
+        dirs: &[Dir],
+        passable: impl Fn(Point<usize>, &T) -> bool,
+    ) -> VecMat<Option<usize>> {
+        let mut dists = VecMat::filled(self.shape(), &None);
+        self.bfs(sources, dirs, passable, |p, _, d| dists[p] = Some(d));
+        dists
+    }
+
+    /// Flood fill from all of `sources`, moving in the given directions onto
+    /// cells for which `passable` holds, keeping track of how each point was
+    /// reached so that paths can be reconstructed.
+    pub fn flood_fill(
+        &self,
+        sources: impl IntoIterator<Item = Point<usize>>,
+        dirs: &[Dir],
+        passable: impl Fn(Point<usize>, &T) -> bool,
+    ) -> FloodFill {
+        let mut reached = HashSet::new();
+        let mut predecessors = VecMat::filled(self.shape(), &None);
+        self.bfs(sources, dirs, passable, |p, from, _| {
+            reached.insert(p);
+            predecessors[p] = from;
+        });
+        FloodFill {
+            reached,
+            predecessors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn maze() -> VecMat<u8> {
+        VecMat::parse_bytes(["..#.", ".##.", "...."]).unwrap()
+    }
+
+    #[test]
+    fn distances() {
+        let open = |_, c: &u8| *c == b'.';
+        let dists = maze().bfs_distances([Point::new(0, 0)], &Dir::CROSS, open);
+        assert_eq!(dists[Point::new(3, 0)], Some(7));
+        assert_eq!(dists[Point::new(2, 0)], None);
+        let dists = maze().bfs_distances([Point::new(0, 0)], &Dir::ALL, open);
+        assert_eq!(dists[Point::new(3, 0)], Some(5));
+        let multi = [Point::new(0, 0), Point::new(3, 0)];
+        let dists = maze().bfs_distances(multi, &Dir::CROSS, open);
+        assert_eq!(dists[Point::new(1, 2)], Some(3));
+    }
+
+    #[test]
+    fn paths() {
+        let fill = maze().flood_fill([Point::new(0, 0)], &Dir::CROSS, |_, c| *c == b'.');
+        assert_eq!(fill.reached.len(), 9);
+        let path = fill.path_to(Point::new(3, 1)).unwrap();
+        assert_eq!(path.len(), 7);
+        assert_eq!(path[0], Point::new(0, 0));
+        assert_eq!(path[6], Point::new(3, 1));
+        assert!(
+            path.windows(2)
+                .all(|w| w[0].delta_to(w[1]).unwrap().manhattan() == 1)
+        );
+        assert_eq!(fill.path_to(Point::new(1, 1)), None);
+    }
+}
@@ -0,0 +1,371 @@
+use std::{
+    mem,
+    ops::{Add, AddAssign, Index, IndexMut, Sub},
+};
+
+use super::Coord;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Delta3<T> {
+    pub dx: T,
+    pub dy: T,
+    pub dz: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T> Delta3<T> {
+    pub const fn new(dx: T, dy: T, dz: T) -> Self {
+        Self { dx, dy, dz }
+    }
+}
+
+impl<T: From<i8>> Delta3<T> {
+    /// Unit steps towards the 6 face-adjacent neighbours
+    pub fn faces() -> [Self; 6] {
+        [
+            (1, 0, 0),
+            (-1, 0, 0),
+            (0, 1, 0),
+            (0, -1, 0),
+            (0, 0, 1),
+            (0, 0, -1),
+        ]
+        .map(|(x, y, z)| Delta3::new(T::from(x), T::from(y), T::from(z)))
+    }
+
+    /// Steps towards all 26 neighbours (sharing a face, edge or corner)
+    pub fn around() -> [Self; 26] {
+        let mut steps = [(0, 0, 0); 26];
+        let offsets =
+            (-1..=1).flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| (x, y, z))));
+        for (slot, d) in steps.iter_mut().zip(offsets.filter(|&d| d != (0, 0, 0))) {
+            *slot = d;
+        }
+        steps.map(|(x, y, z)| Delta3::new(T::from(x), T::from(y), T::from(z)))
+    }
+}
+
+impl<T: Add<Output = T>> Add<Delta3<T>> for Point3<T> {
+    type Output = Point3<T>;
+
+    fn add(self, rhs: Delta3<T>) -> Self::Output {
+        Point3::new(self.x + rhs.dx, self.y + rhs.dy, self.z + rhs.dz)
+    }
+}
+
+impl<T: AddAssign> AddAssign<Delta3<T>> for Point3<T> {
+    fn add_assign(&mut self, rhs: Delta3<T>) {
+        self.x += rhs.dx;
+        self.y += rhs.dy;
+        self.z += rhs.dz;
+    }
+}
+
+/// The delta going from `rhs` to `self`
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Delta3<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Delta3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Coord> Point3<T> {
+    pub fn manhattan_dist(self, other: Self) -> T {
+        self.x.dist(other.x) + self.y.dist(other.y) + self.z.dist(other.z)
+    }
+
+    /// Square of the euclidean distance
+    pub fn dist_sq(self, other: Self) -> T {
+        let dx = self.x.dist(other.x);
+        let dy = self.y.dist(other.y);
+        let dz = self.z.dist(other.z);
+        dx * dx + dy * dy + dz * dz
+    }
+}
+
+impl Point3<isize> {
+    /// The 6 points sharing a face with this one
+    pub fn face_neighbours(&self) -> [Self; 6] {
+        Delta3::faces().map(|d| *self + d)
+    }
+
+    /// The 26 points sharing a face, edge or corner with this one
+    pub fn all_neighbours(&self) -> [Self; 26] {
+        Delta3::around().map(|d| *self + d)
+    }
+}
+
+impl Point3<usize> {
+    pub fn checked_add_signed(&self, d: Delta3<isize>) -> Option<Self> {
+        Some(Self::new(
+            self.x.checked_add_signed(d.dx)?,
+            self.y.checked_add_signed(d.dy)?,
+            self.z.checked_add_signed(d.dz)?,
+        ))
+    }
+
+    /// The (up to 6) points sharing a face with this one,
+    /// skipping those which would have negative coordinates
+    pub fn face_neighbours(&self) -> impl Iterator<Item = Self> + use<> {
+        let p = *self;
+        Delta3::faces()
+            .into_iter()
+            .filter_map(move |d| p.checked_add_signed(d))
+    }
+
+    /// The (up to 26) points sharing a face, edge or corner with this one,
+    /// skipping those which would have negative coordinates
+    pub fn all_neighbours(&self) -> impl Iterator<Item = Self> + use<> {
+        let p = *self;
+        Delta3::around()
+            .into_iter()
+            .filter_map(move |d| p.checked_add_signed(d))
+    }
+}
+
+/// An axis-aligned box, with both corners included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Aabb<T> {
+    pub min: Point3<T>,
+    pub max: Point3<T>,
+}
+
+impl<T: Coord> Aabb<T> {
+    /// The box with the two given opposite corners
+    pub fn new(a: Point3<T>, b: Point3<T>) -> Self {
+        Self {
+            min: Point3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Point3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    /// Number of points in the box
+    pub fn volume(&self) -> T {
+        let Delta3 { dx, dy, dz } = self.max - self.min;
+        (dx + T::ONE) * (dy + T::ONE) * (dz + T::ONE)
+    }
+
+    pub fn contains(&self, p: Point3<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+            && (self.min.z..=self.max.z).contains(&p.z)
+    }
+
+    pub fn contains_box(&self, other: &Self) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Point3::new(
+            self.min.x.max(other.min.x),
+            self.min.y.max(other.min.y),
+            self.min.z.max(other.min.z),
+        );
+        let max = Point3::new(
+            self.max.x.min(other.max.x),
+            self.max.y.min(other.max.y),
+            self.max.z.min(other.max.z),
+        );
+        (min.x <= max.x && min.y <= max.y && min.z <= max.z).then_some(Self { min, max })
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The smallest box containing both
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(
+            Point3::new(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                self.min.z.min(other.min.z),
+            ),
+            Point3::new(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                self.max.z.max(other.max.z),
+            ),
+        )
+    }
+
+    /// The box moved by the given delta
+    pub fn translated(&self, d: Delta3<T>) -> Self {
+        Self {
+            min: self.min + d,
+            max: self.max + d,
+        }
+    }
+
+    pub fn iter_points(&self) -> impl Iterator<Item = Point3<T>>
+    where
+        std::ops::RangeInclusive<T>: Iterator<Item = T>,
+    {
+        let Self { min, max } = *self;
+        (min.z..=max.z).flat_map(move |z| {
+            (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point3::new(x, y, z)))
+        })
+    }
+}
+
+/// A dense 3d grid of values, stored in a flat buffer
+/// as a stack of row-major layers along z
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VecMat3<T> {
+    data: Vec<T>,
+    /// (layers, rows, cols), ie the extent along z, y and x
+    shape: (usize, usize, usize),
+}
+
+impl<T> Index<Point3<usize>> for VecMat3<T> {
+    type Output = T;
+
+    fn index(&self, index: Point3<usize>) -> &Self::Output {
+        self.get(index).expect("indexed point must be in bounds")
+    }
+}
+
+impl<T> IndexMut<Point3<usize>> for VecMat3<T> {
+    fn index_mut(&mut self, index: Point3<usize>) -> &mut Self::Output {
+        self.get_mut(index)
+            .expect("indexed point must be in bounds")
+    }
+}
+
+impl<T> VecMat3<T> {
+    pub fn filled(shape: (usize, usize, usize), val: &T) -> Self
+    where
+        T: Clone,
+    {
+        let (layers, rows, cols) = shape;
+        let data = vec![val.clone(); layers * rows * cols];
+        Self { data, shape }
+    }
+
+    pub fn filled_with(shape: (usize, usize, usize), f: impl Fn(Point3<usize>) -> T) -> Self {
+        let (layers, rows, cols) = shape;
+        let data = (0..layers)
+            .flat_map(|z| (0..rows).flat_map(move |y| (0..cols).map(move |x| Point3::new(x, y, z))))
+            .map(f)
+            .collect();
+        Self { data, shape }
+    }
+
+    /// The (layers, rows, cols) of the grid, ie its extent along z, y and x
+    pub fn shape(&self) -> (usize, usize, usize) {
+        self.shape
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn checked_idx_for(&self, p: Point3<usize>) -> Option<usize> {
+        let (layers, rows, cols) = self.shape;
+        (p.x < cols && p.y < rows && p.z < layers).then(|| (p.z * rows + p.y) * cols + p.x)
+    }
+
+    fn point_for(&self, idx: usize) -> Point3<usize> {
+        let (_, rows, cols) = self.shape;
+        Point3::new(idx % cols, idx / cols % rows, idx / cols / rows)
+    }
+
+    pub fn get(&self, p: Point3<usize>) -> Option<&T> {
+        self.data.get(self.checked_idx_for(p)?)
+    }
+
+    pub fn get_mut(&mut self, p: Point3<usize>) -> Option<&mut T> {
+        let idx = self.checked_idx_for(p)?;
+        self.data.get_mut(idx)
+    }
+
+    pub fn set(&mut self, p: Point3<usize>, val: T) -> T {
+        mem::replace(self.get_mut(p).expect("should be in bounds"), val)
+    }
+
+    /// The z-th layer as a row-major slice
+    pub fn layer(&self, z: usize) -> Option<&[T]> {
+        let (layers, rows, cols) = self.shape;
+        (z < layers).then(|| &self.data[z * rows * cols..(z + 1) * rows * cols])
+    }
+
+    pub fn iter_pos(&self) -> impl Iterator<Item = (Point3<usize>, &T)> {
+        self.data
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (self.point_for(i), c))
+    }
+
+    pub fn for_each_mut(&mut self, mut f: impl FnMut(Point3<usize>, &mut T)) {
+        let (_, rows, cols) = self.shape;
+        for (i, cell) in self.data.iter_mut().enumerate() {
+            f(
+                Point3::new(i % cols, i / cols % rows, i / cols / rows),
+                cell,
+            );
+        }
+    }
+
+    /// The in-bounds face-adjacent neighbours of a point, with their values
+    pub fn face_neighbours(&self, p: Point3<usize>) -> impl Iterator<Item = (Point3<usize>, &T)> {
+        p.face_neighbours()
+            .filter_map(move |n| self.get(n).map(|c| (n, c)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points() {
+        let (a, b) = (Point3::new(162isize, 817, 812), Point3::new(425, 690, 689));
+        assert_eq!(a.dist_sq(b), 263 * 263 + 127 * 127 + 123 * 123);
+        assert_eq!(a.manhattan_dist(b), 263 + 127 + 123);
+        assert_eq!(a + (b - a), b);
+        assert_eq!(Delta3::<isize>::around().len(), 26);
+        let origin = Point3::new(0usize, 0, 0);
+        assert_eq!(origin.face_neighbours().count(), 3);
+        assert_eq!(origin.all_neighbours().count(), 7);
+    }
+
+    #[test]
+    fn bricks() {
+        let a = Aabb::new(Point3::new(1, 0, 1), Point3::new(1, 2, 1));
+        let b = Aabb::new(Point3::new(0, 0, 2), Point3::new(2, 0, 2));
+        assert_eq!(a.volume(), 3);
+        assert!(!a.intersects(&b));
+        let fallen = b.translated(Delta3::new(0, 0, -1));
+        assert_eq!(
+            a.intersection(&fallen),
+            Some(Aabb::new(Point3::new(1, 0, 1), Point3::new(1, 0, 1)))
+        );
+        assert!(a.union(&b).contains_box(&fallen));
+        assert_eq!(a.iter_points().count(), 3);
+    }
+
+    #[test]
+    fn voxels() {
+        let mut v = VecMat3::filled((2, 3, 4), &0);
+        v[Point3::new(3, 2, 1)] = 7;
+        assert_eq!(v.get(Point3::new(4, 0, 0)), None);
+        assert_eq!(v.iter_pos().last(), Some((Point3::new(3, 2, 1), &7)));
+        assert_eq!(v.layer(1).unwrap()[11], 7);
+        assert_eq!(v.face_neighbours(Point3::new(3, 2, 0)).count(), 3);
+        let w = VecMat3::filled_with((2, 3, 4), |p| p.x + 10 * p.y + 100 * p.z);
+        assert!(w.iter_pos().all(|(p, &c)| c == p.x + 10 * p.y + 100 * p.z));
+    }
+}
@@ -0,0 +1,217 @@
+use core::fmt;
+use std::collections::{HashMap, HashSet};
+
+use super::{Delta, GridCells, MatDisplayWith, PlainDisplay, Point, VecMat};
+
+/// An unbounded grid storing only the cells which have been set,
+/// keeping track of the bounding box of all the cells ever inserted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point<isize>, T>,
+    /// top left and bottom right corners (inclusive), None if nothing was inserted
+    bbox: Option<(Point<isize>, Point<isize>)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<(Point<isize>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point<isize>, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(Point<isize>, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point<isize>, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(p, v)| {
+            self.insert(p, v);
+        });
+    }
+}
+
+impl<T> GridCells<T> for SparseGrid<T> {
+    fn shape(&self) -> (usize, usize) {
+        self.shape()
+    }
+
+    fn cell(&self, p: Point<usize>) -> Option<&T> {
+        let (min, _) = self.bbox?;
+        let d = Delta::new(p.x.try_into().ok()?, p.y.try_into().ok()?);
+        self.cells.get(&min.add(d))
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bbox: None,
+        }
+    }
+
+    /// Set the value of a cell, growing the bounding box if needed
+    pub fn insert(&mut self, p: Point<isize>, val: T) -> Option<T> {
+        self.bbox = Some(match self.bbox {
+            None => (p, p),
+            Some((min, max)) => (
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            ),
+        });
+        self.cells.insert(p, val)
+    }
+
+    /// Unset a cell, note that this never shrinks the bounding box
+    pub fn remove(&mut self, p: Point<isize>) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    pub fn get(&self, p: Point<isize>) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn get_mut(&mut self, p: Point<isize>) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    pub fn contains(&self, p: Point<isize>) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point<isize>, &T)> {
+        self.cells.iter().map(|(&p, v)| (p, v))
+    }
+
+    /// Top left and bottom right corners (inclusive) of the bounding box
+    pub fn bbox(&self) -> Option<(Point<isize>, Point<isize>)> {
+        self.bbox
+    }
+
+    /// The (rows, cols) of the bounding box
+    pub fn shape(&self) -> (usize, usize) {
+        match self.bbox {
+            None => (0, 0),
+            Some((min, max)) => ((max.y - min.y + 1) as usize, (max.x - min.x + 1) as usize),
+        }
+    }
+
+    /// The point's position relative to the bounding box's top left corner,
+    /// if it's inside the bounding box
+    pub fn to_local(&self, p: Point<isize>) -> Option<Point<usize>> {
+        let (min, _) = self.bbox?;
+        let local = Point::new(
+            (p.x - min.x).try_into().ok()?,
+            (p.y - min.y).try_into().ok()?,
+        );
+        local.as_in_bounds((self.shape().1, self.shape().0))
+    }
+
+    /// Convert to a dense grid covering the bounding box, filling in the
+    /// missing cells; also returns the offset of the dense grid's origin,
+    /// ie the point `p` ends up at `p - offset`
+    pub fn to_dense(&self, fill: &T) -> (VecMat<T>, Point<isize>)
+    where
+        T: Clone,
+    {
+        let offset = self.bbox.map_or(Point::new(0, 0), |(min, _)| min);
+        let grid = VecMat::filled_with(self.shape(), |p| {
+            GridCells::cell(self, p).unwrap_or(fill).clone()
+        });
+        (grid, offset)
+    }
+
+    pub fn formatter(&self) -> PlainDisplay<'_, T> {
+        MatDisplayWith::new(self, |_, _, c| c)
+    }
+
+    /// Render the bounding box, `f` gets the position relative to its top left corner
+    pub fn formatter_with<F, U>(&self, f: F) -> MatDisplayWith<'_, T, F>
+    where
+        F: Fn(usize, usize, &T) -> U,
+    {
+        MatDisplayWith::new(self, f)
+    }
+
+    pub fn highlighted(&self, highlights: &HashSet<Point<isize>>) -> impl fmt::Display + '_
+    where
+        T: fmt::Display,
+    {
+        struct Highlighted<'a, T> {
+            grid: &'a SparseGrid<T>,
+            highlights: HashSet<Point<usize>>,
+        }
+
+        impl<T> fmt::Display for Highlighted<'_, T>
+        where
+            T: fmt::Display,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.grid.formatter().fmt_highlight(f, &self.highlights)
+            }
+        }
+
+        let highlights = highlights
+            .iter()
+            .filter_map(|&p| self.to_local(p))
+            .collect();
+        Highlighted {
+            grid: self,
+            highlights,
+        }
+    }
+}
+
+impl fmt::Display for SparseGrid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.formatter_with(|_, _, c| *c).fmt(f)
+    }
+}
+
+impl fmt::Display for SparseGrid<u8> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.formatter_with(|_, _, c| *c as char).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bbox_and_dense() {
+        let mut grid: SparseGrid<char> = [(Point::new(-1, 2), 'a'), (Point::new(2, 0), 'b')]
+            .into_iter()
+            .collect();
+        assert_eq!(grid.bbox(), Some((Point::new(-1, 0), Point::new(2, 2))));
+        assert_eq!(grid.shape(), (3, 4));
+        assert_eq!(grid.to_string(), "...b\n....\na...\n");
+        grid.insert(Point::new(0, -1), 'c');
+        let (dense, offset) = grid.to_dense(&' ');
+        assert_eq!(offset, Point::new(-1, -1));
+        assert_eq!(dense[Point::new(3, 1)], 'b');
+        assert_eq!(dense.to_string(), " c  \n   b\n    \na   \n");
+        assert_eq!(grid.to_local(Point::new(3, 0)), None);
+    }
+
+    #[test]
+    fn highlight() {
+        let grid: SparseGrid<char> = [(Point::new(5, 5), 'x'), (Point::new(6, 5), 'y')]
+            .into_iter()
+            .collect();
+        let hl = [Point::new(6, 5)].into_iter().collect();
+        assert_eq!(grid.highlighted(&hl).to_string(), "x\x1b[1;31my\x1b[0m\n");
+    }
+}
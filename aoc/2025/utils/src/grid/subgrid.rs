@@ -0,0 +1,316 @@
+use core::fmt;
+use std::ops::{Index, IndexMut};
+
+use super::{GridCells, MatDisplayWith, PlainDisplay, Point, VecMat};
+
+/// A borrowed rectangular region of a grid, see [`VecMat::sub`];
+/// points are relative to the region's top left corner
+#[derive(Debug)]
+pub struct SubGrid<'a, T> {
+    grid: &'a VecMat<T>,
+    origin: Point<usize>,
+    shape: (usize, usize),
+}
+
+/// A mutably borrowed rectangular region of a grid, see [`VecMat::sub_mut`];
+/// points are relative to the region's top left corner
+#[derive(Debug)]
+pub struct SubGridMut<'a, T> {
+    grid: &'a mut VecMat<T>,
+    origin: Point<usize>,
+    shape: (usize, usize),
+}
+
+impl_copy_view!(SubGrid);
+
+/// Sub-grids are equal if they have the same shape and contents,
+/// wherever they are in whichever grid
+impl<T: PartialEq> PartialEq for SubGrid<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.shape == other.shape && self.iter_rows().eq(other.iter_rows())
+    }
+}
+impl<T: Eq> Eq for SubGrid<'_, T> {}
+
+impl<T: PartialEq> PartialEq<VecMat<T>> for SubGrid<'_, T> {
+    fn eq(&self, other: &VecMat<T>) -> bool {
+        self.shape == other.shape() && self.iter_rows().eq(other.iter_rows())
+    }
+}
+
+impl<T> Index<Point<usize>> for SubGrid<'_, T> {
+    type Output = T;
+
+    fn index(&self, index: Point<usize>) -> &Self::Output {
+        self.get(index).expect("indexed point must be in bounds")
+    }
+}
+
+impl<T> Index<Point<usize>> for SubGridMut<'_, T> {
+    type Output = T;
+
+    fn index(&self, index: Point<usize>) -> &Self::Output {
+        self.get(index).expect("indexed point must be in bounds")
+    }
+}
+
+impl<T> IndexMut<Point<usize>> for SubGridMut<'_, T> {
+    fn index_mut(&mut self, index: Point<usize>) -> &mut Self::Output {
+        self.get_mut(index)
+            .expect("indexed point must be in bounds")
+    }
+}
+
+impl<T> GridCells<T> for SubGrid<'_, T> {
+    fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    fn cell(&self, p: Point<usize>) -> Option<&T> {
+        self.get(p)
+    }
+}
+
+impl fmt::Display for SubGrid<'_, char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.formatter_with(|_, _, c| *c).fmt(f)
+    }
+}
+
+impl fmt::Display for SubGrid<'_, u8> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.formatter_with(|_, _, c| *c as char).fmt(f)
+    }
+}
+
+/// Whether a region with the given origin and (rows, cols) shape fits in the grid
+fn fits<T>(grid: &VecMat<T>, origin: Point<usize>, (rows, cols): (usize, usize)) -> bool {
+    origin.x + cols <= grid.cols() && origin.y + rows <= grid.rows()
+}
+
+impl<'a, T> SubGrid<'a, T> {
+    pub fn origin(&self) -> Point<usize> {
+        self.origin
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    pub fn rows(&self) -> usize {
+        self.shape.0
+    }
+
+    pub fn cols(&self) -> usize {
+        self.shape.1
+    }
+
+    /// The point of the underlying grid corresponding to `p`, if in bounds
+    pub fn to_outer(&self, p: Point<usize>) -> Option<Point<usize>> {
+        p.as_in_bounds((self.cols(), self.rows()))
+            .map(|p| Point::new(p.x + self.origin.x, p.y + self.origin.y))
+    }
+
+    pub fn get(&self, p: Point<usize>) -> Option<&'a T> {
+        self.grid.get(self.to_outer(p)?)
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &'a [T]> {
+        let Self {
+            grid,
+            origin,
+            shape: (rows, cols),
+        } = *self;
+        (origin.y..origin.y + rows).map(move |y| &grid[y][origin.x..origin.x + cols])
+    }
+
+    pub fn iter_pos(&self) -> impl Iterator<Item = (Point<usize>, &'a T)> {
+        self.iter_rows().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, c)| (Point::new(x, y), c))
+        })
+    }
+
+    /// A region of this region, with origin relative to this one's
+    pub fn sub(&self, origin: Point<usize>, shape: (usize, usize)) -> Option<Self> {
+        let (rows, cols) = shape;
+        let fits = origin.x + cols <= self.cols() && origin.y + rows <= self.rows();
+        fits.then(|| SubGrid {
+            grid: self.grid,
+            origin: Point::new(self.origin.x + origin.x, self.origin.y + origin.y),
+            shape,
+        })
+    }
+
+    pub fn formatter(&self) -> PlainDisplay<'_, T> {
+        MatDisplayWith::new(self, |_, _, c| c)
+    }
+
+    pub fn formatter_with<F, U>(&self, f: F) -> MatDisplayWith<'_, T, F>
+    where
+        F: Fn(usize, usize, &T) -> U,
+    {
+        MatDisplayWith::new(self, f)
+    }
+
+    /// Copy the region into a new grid
+    pub fn to_vecmat(&self) -> VecMat<T>
+    where
+        T: Clone,
+    {
+        VecMat::filled_with(self.shape, |p| self[p].clone())
+    }
+}
+
+impl<T> SubGridMut<'_, T> {
+    pub fn origin(&self) -> Point<usize> {
+        self.origin
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    /// Reborrow as an immutable view
+    pub fn as_sub(&self) -> SubGrid<'_, T> {
+        SubGrid {
+            grid: self.grid,
+            origin: self.origin,
+            shape: self.shape,
+        }
+    }
+
+    pub fn get(&self, p: Point<usize>) -> Option<&T> {
+        let outer = self.as_sub().to_outer(p)?;
+        self.grid.get(outer)
+    }
+
+    pub fn get_mut(&mut self, p: Point<usize>) -> Option<&mut T> {
+        let outer = self.as_sub().to_outer(p)?;
+        self.grid.get_mut(outer)
+    }
+
+    pub fn iter_rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let (origin, (rows, cols)) = (self.origin, self.shape);
+        self.grid
+            .data
+            .chunks_exact_mut(self.grid.cols.max(1))
+            .skip(origin.y)
+            .take(rows)
+            .map(move |row| &mut row[origin.x..origin.x + cols])
+    }
+
+    pub fn for_each_mut(&mut self, mut f: impl FnMut(Point<usize>, &mut T)) {
+        for (y, row) in self.iter_rows_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                f(Point::new(x, y), cell);
+            }
+        }
+    }
+
+    pub fn fill(&mut self, val: &T)
+    where
+        T: Clone,
+    {
+        self.iter_rows_mut().for_each(|row| row.fill(val.clone()));
+    }
+
+    /// Overwrite the region with the contents of another region of the same shape
+    pub fn copy_from(&mut self, other: &SubGrid<'_, T>)
+    where
+        T: Clone,
+    {
+        assert_eq!(
+            self.shape,
+            other.shape(),
+            "regions must have the same shape"
+        );
+        for (dst, src) in self.iter_rows_mut().zip(other.iter_rows()) {
+            dst.clone_from_slice(src);
+        }
+    }
+}
+
+impl<T> VecMat<T> {
+    /// Borrow the region with the given top left corner and (rows, cols) shape,
+    /// if it fits in the grid
+    pub fn sub(&self, origin: Point<usize>, shape: (usize, usize)) -> Option<SubGrid<'_, T>> {
+        fits(self, origin, shape).then_some(SubGrid {
+            grid: self,
+            origin,
+            shape,
+        })
+    }
+
+    /// Mutably borrow the region with the given top left corner and (rows, cols) shape,
+    /// if it fits in the grid
+    pub fn sub_mut(
+        &mut self,
+        origin: Point<usize>,
+        shape: (usize, usize),
+    ) -> Option<SubGridMut<'_, T>> {
+        fits(self, origin, shape).then_some(SubGridMut {
+            grid: self,
+            origin,
+            shape,
+        })
+    }
+
+    /// The whole grid as a region
+    pub fn as_sub(&self) -> SubGrid<'_, T> {
+        SubGrid {
+            grid: self,
+            origin: Point::new(0, 0),
+            shape: self.shape(),
+        }
+    }
+
+    /// All the regions with the given (rows, cols) shape, in row-major order
+    /// of their top left corner
+    pub fn windows(&self, shape: (usize, usize)) -> impl Iterator<Item = SubGrid<'_, T>> {
+        let (rows, cols) = shape;
+        let ys = (self.rows() + 1).saturating_sub(rows);
+        let xs = (self.cols() + 1).saturating_sub(cols);
+        (0..ys)
+            .flat_map(move |y| (0..xs).map(move |x| Point::new(x, y)))
+            .map(move |origin| SubGrid {
+                grid: self,
+                origin,
+                shape,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn views() {
+        let mut m = VecMat::parse_bytes(["abcd", "efgh", "ijkl"]).unwrap();
+        let s = m.sub(Point::new(1, 1), (2, 2)).unwrap();
+        assert_eq!(s.to_string(), "fg\njk\n");
+        assert_eq!(s[Point::new(1, 0)], b'g');
+        assert_eq!(s.get(Point::new(2, 0)), None);
+        assert_eq!(s.iter_pos().last(), Some((Point::new(1, 1), &b'k')));
+        assert!(m.sub(Point::new(3, 0), (1, 2)).is_none());
+        let mut sm = m.sub_mut(Point::new(2, 0), (3, 2)).unwrap();
+        sm.fill(&b'.');
+        sm[Point::new(0, 2)] = b'#';
+        assert_eq!(m.to_string(), "ab..\nef..\nij#.\n");
+    }
+
+    #[test]
+    fn windows() {
+        let m = VecMat::parse_bytes(["M.S", ".A.", "M.S", ".A.", "M.S"]).unwrap();
+        let pattern = m.sub(Point::new(0, 0), (3, 3)).unwrap();
+        let windows: Vec<_> = m.windows((3, 3)).collect();
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows.iter().filter(|w| **w == pattern).count(), 2);
+        assert_eq!(windows[1].origin(), Point::new(0, 1));
+        assert_eq!(m.windows((6, 1)).count(), 0);
+        assert_eq!(m.windows((1, 1)).count(), 15);
+        assert!(m.as_sub() == m);
+    }
+}
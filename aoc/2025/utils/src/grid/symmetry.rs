@@ -0,0 +1,331 @@
+use std::ops::{Index, Neg};
+
+use super::{Delta, Dir, GridCells, Point, VecMat};
+
+/// One of the 8 symmetries of a square (the D4 group),
+/// rotations are clockwise as seen on screen (ie with y pointing down)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    Rot90,
+    Rot180,
+    Rot270,
+    /// Mirror left to right
+    FlipH,
+    /// Mirror top to bottom
+    FlipV,
+    /// Mirror along the main diagonal
+    Transpose,
+    /// Mirror along the anti-diagonal
+    AntiTranspose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rot90,
+        Transform::Rot180,
+        Transform::Rot270,
+        Transform::FlipH,
+        Transform::FlipV,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    /// As (k, flip): first mirror left to right if flip, then rotate by k quarter turns
+    fn decompose(self) -> (u8, bool) {
+        match self {
+            Transform::Identity => (0, false),
+            Transform::Rot90 => (1, false),
+            Transform::Rot180 => (2, false),
+            Transform::Rot270 => (3, false),
+            Transform::FlipH => (0, true),
+            Transform::AntiTranspose => (1, true),
+            Transform::FlipV => (2, true),
+            Transform::Transpose => (3, true),
+        }
+    }
+
+    fn compose(k: u8, flip: bool) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.decompose() == (k % 4, flip))
+            .unwrap()
+    }
+
+    /// The transform which undoes this one
+    pub fn inverse(self) -> Self {
+        match self {
+            Transform::Rot90 => Transform::Rot270,
+            Transform::Rot270 => Transform::Rot90,
+            t => t,
+        }
+    }
+
+    /// The transform obtained by applying self and then other
+    pub fn then(self, other: Self) -> Self {
+        // mirroring reverses the direction of a rotation applied before it
+        let (ka, fa) = self.decompose();
+        let (kb, fb) = other.decompose();
+        let k = if fb { kb + 4 - ka } else { kb + ka };
+        Self::compose(k, fa != fb)
+    }
+
+    /// Whether the transform swaps rows and columns
+    pub fn swaps_axes(self) -> bool {
+        self.decompose().0 % 2 == 1
+    }
+
+    /// The (rows, cols) shape of a grid of the given shape after the transform
+    pub fn shape(self, (rows, cols): (usize, usize)) -> (usize, usize) {
+        if self.swaps_axes() {
+            (cols, rows)
+        } else {
+            (rows, cols)
+        }
+    }
+
+    /// Where a point of a grid with the given (rows, cols) shape
+    /// ends up after the transform
+    pub fn point(self, p: Point<usize>, (rows, cols): (usize, usize)) -> Point<usize> {
+        let (w, h) = (cols, rows);
+        let Point { x, y } = p;
+        match self {
+            Transform::Identity => Point::new(x, y),
+            Transform::Rot90 => Point::new(h - 1 - y, x),
+            Transform::Rot180 => Point::new(w - 1 - x, h - 1 - y),
+            Transform::Rot270 => Point::new(y, w - 1 - x),
+            Transform::FlipH => Point::new(w - 1 - x, y),
+            Transform::FlipV => Point::new(x, h - 1 - y),
+            Transform::Transpose => Point::new(y, x),
+            Transform::AntiTranspose => Point::new(h - 1 - y, w - 1 - x),
+        }
+    }
+
+    pub fn delta<T: Neg<Output = T>>(self, d: Delta<T>) -> Delta<T> {
+        let Delta { dx, dy } = d;
+        match self {
+            Transform::Identity => Delta::new(dx, dy),
+            Transform::Rot90 => Delta::new(-dy, dx),
+            Transform::Rot180 => Delta::new(-dx, -dy),
+            Transform::Rot270 => Delta::new(dy, -dx),
+            Transform::FlipH => Delta::new(-dx, dy),
+            Transform::FlipV => Delta::new(dx, -dy),
+            Transform::Transpose => Delta::new(dy, dx),
+            Transform::AntiTranspose => Delta::new(-dy, -dx),
+        }
+    }
+
+    pub fn dir(self, d: Dir) -> Dir {
+        let (k, flip) = self.decompose();
+        let d = if flip {
+            d.rotate_by(-2 * d.id() as isize)
+        } else {
+            d
+        };
+        d.rotate_by(2 * k as isize)
+    }
+}
+
+impl Dir {
+    /// The direction after applying the transform
+    pub fn transformed(self, t: Transform) -> Self {
+        t.dir(self)
+    }
+}
+
+impl<T: Neg<Output = T>> Delta<T> {
+    /// The delta after applying the transform
+    pub fn transformed(self, t: Transform) -> Self {
+        t.delta(self)
+    }
+}
+
+/// A zero-copy view of a grid under a [`Transform`],
+/// see [`VecMat::view_transformed`]
+#[derive(Debug)]
+pub struct Transformed<'a, T> {
+    grid: &'a VecMat<T>,
+    transform: Transform,
+}
+
+impl_copy_view!(Transformed);
+
+impl<T> Index<Point<usize>> for Transformed<'_, T> {
+    type Output = T;
+
+    fn index(&self, index: Point<usize>) -> &Self::Output {
+        self.get(index).expect("indexed point must be in bounds")
+    }
+}
+
+impl<T> GridCells<T> for Transformed<'_, T> {
+    fn shape(&self) -> (usize, usize) {
+        self.shape()
+    }
+
+    fn cell(&self, p: Point<usize>) -> Option<&T> {
+        self.get(p)
+    }
+}
+
+impl<'a, T> Transformed<'a, T> {
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        self.transform.shape(self.grid.shape())
+    }
+
+    pub fn rows(&self) -> usize {
+        self.shape().0
+    }
+
+    pub fn cols(&self) -> usize {
+        self.shape().1
+    }
+
+    /// The point of the underlying grid shown at `p` in the view
+    pub fn source_point(&self, p: Point<usize>) -> Option<Point<usize>> {
+        let (rows, cols) = self.shape();
+        p.as_in_bounds((cols, rows))
+            .map(|p| self.transform.inverse().point(p, (rows, cols)))
+    }
+
+    pub fn get(&self, p: Point<usize>) -> Option<&'a T> {
+        self.grid.get(self.source_point(p)?)
+    }
+
+    pub fn iter_pos(&self) -> impl Iterator<Item = (Point<usize>, &'a T)> {
+        let view = *self;
+        let (rows, cols) = self.shape();
+        (0..rows)
+            .flat_map(move |y| (0..cols).map(move |x| Point::new(x, y)))
+            .map(move |p| (p, view.get(p).expect("in bounds")))
+    }
+
+    /// Copy the view into a new grid
+    pub fn to_vecmat(&self) -> VecMat<T>
+    where
+        T: Clone,
+    {
+        VecMat::filled_with(self.shape(), |p| self[p].clone())
+    }
+}
+
+impl<T> VecMat<T> {
+    /// View the grid under the given transform, without copying it
+    pub fn view_transformed(&self, transform: Transform) -> Transformed<'_, T> {
+        Transformed {
+            grid: self,
+            transform,
+        }
+    }
+
+    /// A copy of the grid under the given transform
+    pub fn transformed(&self, transform: Transform) -> Self
+    where
+        T: Clone,
+    {
+        self.view_transformed(transform).to_vecmat()
+    }
+
+    /// Rotate a quarter turn clockwise
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(Transform::Rot90)
+    }
+
+    /// Rotate a quarter turn counterclockwise
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(Transform::Rot270)
+    }
+
+    pub fn rotate_180(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(Transform::Rot180)
+    }
+
+    /// Mirror left to right
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(Transform::FlipH)
+    }
+
+    /// Mirror top to bottom
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(Transform::FlipV)
+    }
+
+    /// Views of the grid in all of its 8 orientations
+    pub fn orientations(&self) -> [Transformed<'_, T>; 8] {
+        Transform::ALL.map(|t| self.view_transformed(t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> VecMat<u8> {
+        VecMat::parse_bytes(["abc", "def"]).unwrap()
+    }
+
+    #[test]
+    fn copies() {
+        let m = sample();
+        assert_eq!(m.rotate_cw().to_string(), "da\neb\nfc\n");
+        assert_eq!(m.rotate_ccw().to_string(), "cf\nbe\nad\n");
+        assert_eq!(m.rotate_180().to_string(), "fed\ncba\n");
+        assert_eq!(m.flip_horizontal().to_string(), "cba\nfed\n");
+        assert_eq!(m.flip_vertical().to_string(), "def\nabc\n");
+        assert_eq!(m.transformed(Transform::Transpose), m.transpose());
+        assert_eq!(
+            m.transformed(Transform::AntiTranspose).to_string(),
+            "fc\neb\nda\n"
+        );
+    }
+
+    #[test]
+    fn group() {
+        let m = sample();
+        for a in Transform::ALL {
+            assert_eq!(a.then(a.inverse()), Transform::Identity);
+            for b in Transform::ALL {
+                let twice = m.transformed(a).transformed(b);
+                assert_eq!(twice, m.transformed(a.then(b)), "{a:?} then {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn headings() {
+        let m = sample();
+        let (p, d) = (Point::new(0, 0), Dir::E);
+        for t in Transform::ALL {
+            let moved = t.point(p.neighbour(d), m.shape());
+            let rotated = t
+                .point(p, m.shape())
+                .wrapping_add_signed(t.delta(d.to_delta()));
+            assert_eq!(moved, rotated, "{t:?}");
+            assert_eq!(d.transformed(t).to_delta::<isize>(), t.delta(d.to_delta()));
+            assert_eq!(
+                Dir::NE.transformed(t).to_delta::<isize>(),
+                t.delta(Dir::NE.to_delta())
+            );
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::hash::{Hash, Hasher};
+
+use super::{Dir, VecMat};
+
+/// How a cell behaves when tilting a grid, see [`VecMat::tilt`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slide {
+    /// Rolls as far as it can
+    Movable,
+    /// Never moves and stops what rolls into it
+    Blocking,
+    /// Can be rolled into
+    Empty,
+}
+
+/// FNV-1a, so hashes don't depend on the process or the std version
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+impl<T: Hash> VecMat<T> {
+    /// A hash of the shape and contents which is the same across runs,
+    /// eg to detect states repeating in a simulation
+    pub fn stable_hash(&self) -> u64 {
+        let mut h = Fnv(0xcbf29ce484222325);
+        self.shape().hash(&mut h);
+        self.data.hash(&mut h);
+        h.finish()
+    }
+}
+
+impl<T> VecMat<T> {
+    /// The lines of the grid along `dir` (which must be N, E, S or W) as
+    /// indices in `data`, each starting from the edge cells roll towards
+    fn tilt_lines(&self, dir: Dir) -> Vec<Vec<usize>> {
+        let (rows, cols) = self.shape();
+        let (ys, xs): (Vec<_>, Vec<_>) = ((0..rows).collect(), (0..cols).collect());
+        let rev = |v: &Vec<usize>| v.iter().rev().copied().collect::<Vec<_>>();
+        let (outer, inner, vertical) = match dir {
+            Dir::N => (xs, ys, true),
+            Dir::S => (xs, rev(&ys), true),
+            Dir::W => (ys, xs, false),
+            Dir::E => (ys, rev(&xs), false),
+            _ => panic!("can only tilt in a cardinal direction, not {dir:?}"),
+        };
+        outer
+            .iter()
+            .map(|&a| {
+                inner
+                    .iter()
+                    .map(|&b| if vertical { b * cols + a } else { a * cols + b })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Move every movable cell as far as possible in direction `dir`
+    /// (N, E, S or W), until it's stopped by a blocking cell, another
+    /// movable one or the edge; empty cells take the places they leave
+    pub fn tilt(&mut self, dir: Dir, classify: impl Fn(&T) -> Slide) {
+        for line in self.tilt_lines(dir) {
+            // the next place a movable cell would stop at
+            let mut free = 0;
+            for i in 0..line.len() {
+                match classify(&self.data[line[i]]) {
+                    Slide::Movable => {
+                        self.data.swap(line[free], line[i]);
+                        free += 1;
+                    }
+                    Slide::Blocking => free = i + 1,
+                    Slide::Empty => {}
+                }
+            }
+        }
+    }
+
+    /// The grid tilted in direction `dir` (see [`VecMat::tilt`]) and its
+    /// [stable hash](VecMat::stable_hash)
+    pub fn tilted(&self, dir: Dir, classify: impl Fn(&T) -> Slide) -> (Self, u64)
+    where
+        T: Clone + Hash,
+    {
+        let mut tilted = self.clone();
+        tilted.tilt(dir, classify);
+        let hash = tilted.stable_hash();
+        (tilted, hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn rocks(c: &u8) -> Slide {
+        match c {
+            b'O' => Slide::Movable,
+            b'#' => Slide::Blocking,
+            _ => Slide::Empty,
+        }
+    }
+
+    fn load(m: &VecMat<u8>) -> usize {
+        let rows = m.rows();
+        m.iter_pos()
+            .filter(|(_, c)| **c == b'O')
+            .map(|(p, _)| rows - p.y)
+            .sum()
+    }
+
+    /// the platform from 2023 day 14's example
+    fn platform() -> VecMat<u8> {
+        VecMat::parse_bytes([
+            "O....#....",
+            "O.OO#....#",
+            ".....##...",
+            "OO.#O....O",
+            ".O.....O#.",
+            "O.#..O.#.#",
+            "..O..#O..O",
+            ".......O..",
+            "#....###..",
+            "#OO..#....",
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn tilt() {
+        let (north, hash) = platform().tilted(Dir::N, rocks);
+        assert_eq!(load(&north), 136);
+        assert_eq!(hash, north.stable_hash());
+        assert_ne!(hash, platform().stable_hash());
+        let mut m = VecMat::parse_bytes(["O.O#.O.", ".O..O#O"]).unwrap();
+        m.tilt(Dir::E, rocks);
+        assert_eq!(m.to_string(), ".OO#..O\n...OO#O\n");
+        m.tilt(Dir::W, rocks);
+        assert_eq!(m.to_string(), "OO.#O..\nOO...#O\n");
+        m.tilt(Dir::S, rocks);
+        assert_eq!(m.to_string(), "OO.#...\nOO..O#O\n");
+    }
+
+    #[test]
+    fn spin_cycles() {
+        let mut m = platform();
+        let mut seen = HashMap::new();
+        let target = 1_000_000_000;
+        let mut i = 0;
+        while i < target {
+            for dir in [Dir::N, Dir::W, Dir::S, Dir::E] {
+                m.tilt(dir, rocks);
+            }
+            i += 1;
+            if let Some(prev) = seen.insert(m.stable_hash(), i) {
+                let period = i - prev;
+                i += (target - i) / period * period;
+            }
+        }
+        assert_eq!(load(&m), 64);
+    }
+}
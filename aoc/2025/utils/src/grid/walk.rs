@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+
+use super::{Dir, Point, VecMat};
+
+/// The states an agent went through on a grid, see [`VecMat::walk`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Walk {
+    /// Every (cell, direction it left the cell in) in order, each at most once
+    pub path: Vec<(Point<usize>, Dir)>,
+    /// If the agent ended up in a loop, the index in `path` where the loop starts
+    pub loop_start: Option<usize>,
+}
+
+impl Walk {
+    pub fn looped(&self) -> bool {
+        self.loop_start.is_some()
+    }
+
+    /// The first state that's part of the loop, if any
+    pub fn loop_entry(&self) -> Option<(Point<usize>, Dir)> {
+        self.loop_start.map(|i| self.path[i])
+    }
+
+    /// The states repeating forever (empty if the agent didn't loop)
+    pub fn cycle(&self) -> &[(Point<usize>, Dir)] {
+        &self.path[self.loop_start.unwrap_or(self.path.len())..]
+    }
+
+    /// The distinct cells visited, in order of first visit
+    pub fn cells(&self) -> Vec<Point<usize>> {
+        let mut seen = HashSet::new();
+        self.path
+            .iter()
+            .map(|&(p, _)| p)
+            .filter(|&p| seen.insert(p))
+            .collect()
+    }
+}
+
+/// Walks agents on grids of a given shape, keeping the visited states
+/// as one bitmask of directions per cell; reusing a walker for many walks
+/// on the same grid avoids reallocating it
+#[derive(Debug, Clone)]
+pub struct Walker {
+    visited: VecMat<u8>,
+}
+
+impl Walker {
+    /// A walker for grids of the given (rows, cols) shape
+    pub fn new(shape: (usize, usize)) -> Self {
+        Self {
+            visited: VecMat::filled(shape, &0),
+        }
+    }
+
+    /// Walk from `start` heading `dir`: at every cell `step(cell, heading)`
+    /// gives the direction to leave it in (None to stop there), until the
+    /// agent leaves the grid, stops, or repeats a state (ie loops)
+    pub fn walk(
+        &mut self,
+        start: Point<usize>,
+        dir: Dir,
+        mut step: impl FnMut(Point<usize>, Dir) -> Option<Dir>,
+    ) -> Walk {
+        let mut path = Vec::new();
+        let mut loop_start = None;
+        let (mut pos, mut dir) = (start, dir);
+        while self.visited.get(pos).is_some() {
+            let Some(next) = step(pos, dir) else {
+                break;
+            };
+            let bit = 1 << next.id();
+            let mask = &mut self.visited[pos];
+            if *mask & bit != 0 {
+                loop_start = path.iter().position(|&s| s == (pos, next));
+                break;
+            }
+            *mask |= bit;
+            path.push((pos, next));
+            (pos, dir) = (pos.neighbour(next), next);
+        }
+        // only reset what we touched, so a walk costs the length of its path
+        for &(p, _) in &path {
+            self.visited[p] = 0;
+        }
+        Walk { path, loop_start }
+    }
+}
+
+impl<T> VecMat<T> {
+    /// Walk an agent from `start` heading `dir`, see [`Walker::walk`]
+    pub fn walk(
+        &self,
+        start: Point<usize>,
+        dir: Dir,
+        step: impl FnMut(Point<usize>, Dir) -> Option<Dir>,
+    ) -> Walk {
+        Walker::new(self.shape()).walk(start, dir, step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the lab from 2024 day 6's example
+    fn lab() -> VecMat<u8> {
+        VecMat::parse_bytes([
+            "....#.....",
+            ".........#",
+            "..........",
+            "..#.......",
+            ".......#..",
+            "..........",
+            ".#..^.....",
+            "........#.",
+            "#.........",
+            "......#...",
+        ])
+        .unwrap()
+    }
+
+    fn guard(lab: &VecMat<u8>) -> impl FnMut(Point<usize>, Dir) -> Option<Dir> + '_ {
+        |p, mut dir| {
+            while lab.get(p.neighbour(dir)) == Some(&b'#') {
+                dir = dir.clockwise_cross();
+            }
+            Some(dir)
+        }
+    }
+
+    #[test]
+    fn guard_walk() {
+        let mut lab = lab();
+        let start = lab.find(&b'^').unwrap();
+        let walk = lab.walk(start, Dir::N, guard(&lab));
+        assert!(!walk.looped());
+        assert_eq!(walk.cells().len(), 41);
+        assert_eq!(walk.cycle(), []);
+        lab[Point::new(3, 6)] = b'#';
+        let walk = lab.walk(start, Dir::N, guard(&lab));
+        assert!(walk.looped());
+        assert_eq!(walk.loop_entry(), Some((start, Dir::N)));
+        assert_eq!(walk.cycle().len(), walk.path.len());
+    }
+
+    #[test]
+    fn reuse() {
+        let mut lab = lab();
+        let start = lab.find(&b'^').unwrap();
+        let mut walker = Walker::new(lab.shape());
+        let candidates: Vec<_> = lab.walk(start, Dir::N, guard(&lab)).cells();
+        let mut loops = 0;
+        for &p in &candidates[1..] {
+            lab[p] = b'#';
+            loops += walker.walk(start, Dir::N, guard(&lab)).looped() as usize;
+            lab[p] = b'.';
+        }
+        assert_eq!(loops, 6);
+        // the walker was left clean, so walks are unaffected by the previous ones
+        let walk = walker.walk(start, Dir::N, guard(&lab));
+        assert_eq!(walk, lab.walk(start, Dir::N, guard(&lab)));
+    }
+}
@@ -0,0 +1,148 @@
+use std::{
+    collections::{HashMap, VecDeque, hash_map::Entry},
+    ops::Index,
+};
+
+use super::{Dir, Point, VecMat};
+
+/// A view of a grid as tiling the whole plane,
+/// indexed by signed points which wrap around the grid's edges
+#[derive(Debug)]
+pub struct Wrapping<'a, T> {
+    grid: &'a VecMat<T>,
+}
+
+impl_copy_view!(Wrapping);
+
+impl<T> Index<Point<isize>> for Wrapping<'_, T> {
+    type Output = T;
+
+    fn index(&self, index: Point<isize>) -> &Self::Output {
+        &self.grid[self.wrap(index)]
+    }
+}
+
+impl<'a, T> Wrapping<'a, T> {
+    pub fn new(grid: &'a VecMat<T>) -> Self {
+        assert!(!grid.is_empty(), "can't tile the plane with an empty grid");
+        Self { grid }
+    }
+
+    pub fn grid(&self) -> &'a VecMat<T> {
+        self.grid
+    }
+
+    fn size(&self) -> Point<isize> {
+        Point::new(self.grid.cols() as isize, self.grid.rows() as isize)
+    }
+
+    /// The position within the base grid corresponding to the point
+    pub fn wrap(&self, p: Point<isize>) -> Point<usize> {
+        let size = self.size();
+        Point::new(
+            p.x.rem_euclid(size.x) as usize,
+            p.y.rem_euclid(size.y) as usize,
+        )
+    }
+
+    /// Which copy of the grid the point lies in,
+    /// the base grid is the (0, 0) tile
+    pub fn tile(&self, p: Point<isize>) -> Point<isize> {
+        let size = self.size();
+        Point::new(p.x.div_euclid(size.x), p.y.div_euclid(size.y))
+    }
+
+    pub fn get(&self, p: Point<isize>) -> &'a T {
+        &self.grid[self.wrap(p)]
+    }
+
+    /// The point's neighbours in the given directions, with their values
+    pub fn neighbours<'d>(
+        &self,
+        p: Point<isize>,
+        dirs: &'d [Dir],
+    ) -> impl Iterator<Item = (Point<isize>, &'a T)> + 'd
+    where
+        'a: 'd,
+    {
+        let view = *self;
+        dirs.iter().map(move |&dir| {
+            let n = p.add(dir.to_delta());
+            (n, view.get(n))
+        })
+    }
+
+    /// Distances from `sources` to all points at most `max_dist` steps away,
+    /// moving in the given directions onto cells for which `passable` holds
+    pub fn bfs_distances(
+        &self,
+        sources: impl IntoIterator<Item = Point<isize>>,
+        dirs: &[Dir],
+        max_dist: usize,
+        passable: impl Fn(Point<isize>, &T) -> bool,
+    ) -> HashMap<Point<isize>, usize> {
+        let mut dists = HashMap::new();
+        let mut queue = VecDeque::new();
+        for s in sources {
+            if let Entry::Vacant(e) = dists.entry(s) {
+                e.insert(0);
+                queue.push_back((s, 0));
+            }
+        }
+        while let Some((p, dist)) = queue.pop_front() {
+            if dist == max_dist {
+                continue;
+            }
+            for (n, cell) in self.neighbours(p, dirs) {
+                if let Entry::Vacant(e) = dists.entry(n)
+                    && passable(n, cell)
+                {
+                    e.insert(dist + 1);
+                    queue.push_back((n, dist + 1));
+                }
+            }
+        }
+        dists
+    }
+}
+
+impl<T> VecMat<T> {
+    /// View the grid as infinitely repeated in every direction
+    pub fn wrapping(&self) -> Wrapping<'_, T> {
+        Wrapping::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiling() {
+        let grid = VecMat::parse_bytes(["ab", "cd", "ef"]).unwrap();
+        let view = grid.wrapping();
+        assert_eq!(view[Point::new(-1, -1)], b'f');
+        assert_eq!(view[Point::new(4, 4)], b'c');
+        assert_eq!(view.tile(Point::new(-1, 3)), Point::new(-1, 1));
+        assert_eq!(view.tile(Point::new(1, 2)), Point::new(0, 0));
+        let around: Vec<_> = view
+            .neighbours(Point::new(0, 0), &Dir::CROSS)
+            .map(|(_, c)| *c)
+            .collect();
+        assert_eq!(around, b"ebcb");
+    }
+
+    #[test]
+    fn infinite_garden() {
+        let garden = VecMat::parse_bytes(["...", ".#.", "..."]).unwrap();
+        let dists = garden
+            .wrapping()
+            .bfs_distances([Point::new(0, 0)], &Dir::CROSS, 6, |_, c| *c == b'.');
+        let even = dists.values().filter(|&&d| d % 2 == 0).count();
+        // diamond of radius 6 has 49 even cells, minus the 6 rocks on them
+        assert_eq!(even, 43);
+        assert_eq!(dists.get(&Point::new(-2, -2)), None);
+        assert_eq!(dists.get(&Point::new(-1, -1)), Some(&2));
+        assert_eq!(dists.get(&Point::new(0, 7)), None);
+    }
+}
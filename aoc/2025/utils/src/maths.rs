@@ -5,15 +5,15 @@ where
         + std::ops::Rem<Output = T>
         + std::ops::Mul<Output = T>
         + std::ops::Sub<Output = T>
-        + std::ops::Neg<Output = T>
         + Ord
         + Default, // assuming default is 0
 {
+    // subtract rather than negate so unsigned types work too
     if a < T::default() {
-        a = -a;
+        a = T::default() - a;
     }
     if b < T::default() {
-        b = -b;
+        b = T::default() - b;
     }
     while b != T::default() {
         let t = b;
@@ -31,7 +31,6 @@ where
         + std::ops::Div<Output = T>
         + std::ops::Sub<Output = T>
         + std::ops::Rem<Output = T>
-        + std::ops::Neg<Output = T>
         + Ord
         + Default, // assuming default is 0
 {